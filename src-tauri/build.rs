@@ -1,34 +1,105 @@
 use chrono::prelude::*;
 use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+const UNKNOWN: &str = "unknown";
+
+// Run git, None when it is missing or this is not a checkout (e.g. a source tarball)
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+// An explicitly set environment variable wins over anything detected
+fn env_override(name: &str) -> Option<String> {
+    println!("cargo:rerun-if-env-changed={}", name);
+    env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+// Rerun when HEAD moves: HEAD itself for checkouts and branch switches, the ref it
+// points to for new commits, and packed-refs for refs that were packed away. The index
+// changes with staging and with `git status` refreshing it, which keeps GIT_DIRTY current.
+fn watch_git() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    let mut paths = vec!["HEAD".to_string(), "packed-refs".to_string(), "index".to_string()];
+    if let Some(head_ref) = git(&["symbolic-ref", "-q", "HEAD"]) {
+        paths.push(head_ref);
+    }
+    for path in paths {
+        if let Some(path) = git(&["rev-parse", "--git-path", &path]) {
+            let path = manifest_dir.join(path);
+            if path.exists() {
+                println!("cargo:rerun-if-changed={}", path.display());
+            }
+        }
+    }
+}
+
+// SOURCE_DATE_EPOCH for reproducible builds, the current time otherwise
+fn build_time() -> String {
+    let time = env_override("SOURCE_DATE_EPOCH")
+        .and_then(|epoch| epoch.trim().parse::<i64>().ok())
+        .and_then(|epoch| DateTime::from_timestamp(epoch, 0))
+        .unwrap_or_else(Utc::now);
+    time.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
 
 fn main() {
-    // Let Tauri set up its stuff
-    tauri_build::build();
-
-    // Get current Git commit hash
-    let git_hash = std::process::Command::new("git")
-        .args(["rev-parse", "HEAD"])
-        .output()
-        .expect("Failed to get Git commit hash");
-    let git_hash = String::from_utf8(git_hash.stdout).expect("Invalid UTF-8 in Git hash");
-
-    // Get current Git branch
-    let git_branch = std::process::Command::new("git")
-        .args(["rev-parse", "--abbrev-ref", "HEAD"])
-        .output()
-        .expect("Failed to get Git branch");
-    let git_branch = String::from_utf8(git_branch.stdout).expect("Invalid UTF-8 in Git branch");
-
-    // Get current build time in RFC3339 format
-    let build_time = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    // Let Tauri set up its stuff. Capabilities in subdirectories belong to optional features
+    // and are added at runtime.
+    let attributes = tauri_build::Attributes::new().capabilities_path_pattern("./capabilities/*.json");
+    tauri_build::try_build(attributes).expect("Failed to run tauri-build");
+
+    let in_checkout = git(&["rev-parse", "--git-dir"]).is_some();
+    if in_checkout {
+        watch_git();
+    }
+
+    let git_hash = env_override("GIT_HASH")
+        .or_else(|| git(&["rev-parse", "HEAD"]))
+        .unwrap_or_else(|| UNKNOWN.to_string());
+    let git_branch = env_override("GIT_BRANCH")
+        .or_else(|| git(&["rev-parse", "--abbrev-ref", "HEAD"]))
+        .unwrap_or_else(|| UNKNOWN.to_string());
+    // "true" or "false"
+    let git_dirty = env_override("GIT_DIRTY")
+        .or_else(|| {
+            git(&["status", "--porcelain", "--untracked-files=no"]).map(|s| (!s.is_empty()).to_string())
+        })
+        .unwrap_or_else(|| UNKNOWN.to_string());
+    let build_time = env_override("BUILD_TIME").unwrap_or_else(build_time);
+
+    // The compiler building this crate
+    let rustc_version = env::var("RUSTC")
+        .ok()
+        .and_then(|rustc| Command::new(rustc).arg("--version").output().ok())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty())
+        .unwrap_or_else(|| UNKNOWN.to_string());
+
+    if git_hash == UNKNOWN {
+        println!("cargo:warning=git is not available, build metadata is incomplete");
+    }
 
     // Emit values as compile-time env vars
-    println!("cargo:rustc-env=GIT_HASH=\"{}\"", git_hash.trim());
-    println!("cargo:rustc-env=GIT_BRANCH=\"{}\"", git_branch.trim());
-    println!("cargo:rustc-env=BUILD_TIME=\"{}\"", build_time);
+    println!("cargo:rustc-env=GIT_HASH={}", git_hash);
+    println!("cargo:rustc-env=GIT_BRANCH={}", git_branch);
+    println!("cargo:rustc-env=GIT_DIRTY={}", git_dirty);
+    println!("cargo:rustc-env=BUILD_TIME={}", build_time);
+    println!("cargo:rustc-env=RUSTC_VERSION={}", rustc_version);
 
-    // Add C++ standard library linking for Android targets
     let target = env::var("TARGET").unwrap_or_default();
+    println!("cargo:rustc-env=BUILD_TARGET={}", target);
+    println!("cargo:rustc-env=BUILD_PROFILE={}", env::var("PROFILE").unwrap_or_default());
+
+    // Add C++ standard library linking for Android targets
     if target.contains("android") {
         println!("cargo:rustc-link-lib=c++_shared");
     }
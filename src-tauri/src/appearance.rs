@@ -0,0 +1,200 @@
+use log::{info, warn};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Theme};
+
+#[cfg(desktop)]
+use crate::misc::query;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemAppearance {
+    // "dark" or "light"
+    pub theme: String,
+    pub high_contrast: bool,
+    // "#rrggbb", None when the OS has no accent color setting
+    pub accent_color: Option<String>,
+}
+
+// Focus changes come in bursts, the appearance is re-read at most this often on focus
+const FOCUS_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+lazy_static::lazy_static! {
+    // Last appearance sent to the windows, used to only emit actual changes
+    static ref LAST_APPEARANCE: Mutex<Option<SystemAppearance>> = Mutex::new(None);
+    static ref LAST_FOCUS_CHECK: Mutex<Option<Instant>> = Mutex::new(None);
+}
+
+// Value of a registry entry, e.g. "0xffd77800" for a REG_DWORD
+#[cfg(target_os = "windows")]
+fn registry_value(key: &str, name: &str) -> Option<String> {
+    let output = query("reg", &["query", key, "/v", name])?;
+    output
+        .lines()
+        .find(|line| line.trim_start().starts_with(name))
+        .and_then(|line| line.split_whitespace().nth(2))
+        .map(str::to_string)
+}
+
+#[cfg(target_os = "windows")]
+fn high_contrast() -> bool {
+    // HCF_HIGHCONTRASTON is the lowest bit of the flags
+    registry_value(r"HKCU\Control Panel\Accessibility\HighContrast", "Flags")
+        .and_then(|flags| flags.parse::<u32>().ok())
+        .map(|flags| flags & 1 == 1)
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "windows")]
+fn accent_color() -> Option<String> {
+    // Stored as 0xAABBGGRR
+    let value = registry_value(r"HKCU\Software\Microsoft\Windows\DWM", "AccentColor")?;
+    let abgr = u32::from_str_radix(value.trim_start_matches("0x"), 16).ok()?;
+    Some(format!(
+        "#{:02x}{:02x}{:02x}",
+        abgr & 0xff,
+        (abgr >> 8) & 0xff,
+        (abgr >> 16) & 0xff
+    ))
+}
+
+#[cfg(target_os = "macos")]
+fn high_contrast() -> bool {
+    query("defaults", &["read", "com.apple.universalaccess", "increaseContrast"])
+        .map(|value| value == "1")
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "macos")]
+fn accent_color() -> Option<String> {
+    // Missing when the accent color is the default (blue) or multicolor
+    let index = query("defaults", &["read", "-g", "AppleAccentColor"])
+        .and_then(|value| value.parse::<i32>().ok())
+        .unwrap_or(4);
+    let color = match index {
+        -1 => "#8e8e93",
+        0 => "#ff3b30",
+        1 => "#ff9500",
+        2 => "#ffcc00",
+        3 => "#28cd41",
+        5 => "#af52de",
+        6 => "#ff2d55",
+        _ => "#007aff",
+    };
+    Some(color.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn gsettings(schema: &str, key: &str) -> Option<String> {
+    query("gsettings", &["get", schema, key]).map(|value| value.trim_matches('\'').to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn high_contrast() -> bool {
+    gsettings("org.gnome.desktop.a11y.interface", "high-contrast")
+        .map(|value| value == "true")
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "linux")]
+fn accent_color() -> Option<String> {
+    // GNOME 47 and newer
+    let color = match gsettings("org.gnome.desktop.interface", "accent-color")?.as_str() {
+        "blue" => "#3584e4",
+        "teal" => "#2190a4",
+        "green" => "#3a944a",
+        "yellow" => "#c88800",
+        "orange" => "#ed5b00",
+        "red" => "#e62d42",
+        "pink" => "#d56199",
+        "purple" => "#9141ac",
+        "slate" => "#6f8396",
+        _ => return None,
+    };
+    Some(color.to_string())
+}
+
+#[cfg(any(target_os = "android", target_os = "ios"))]
+fn high_contrast() -> bool {
+    false
+}
+
+#[cfg(any(target_os = "android", target_os = "ios"))]
+fn accent_color() -> Option<String> {
+    None
+}
+
+fn current_appearance(app: &AppHandle) -> SystemAppearance {
+    // Every window follows the system theme, the main window may be closed
+    let theme = app
+        .get_webview_window("main")
+        .into_iter()
+        .chain(app.webview_windows().into_values())
+        .find_map(|window| window.theme().ok())
+        .unwrap_or(Theme::Light);
+
+    SystemAppearance {
+        theme: match theme {
+            Theme::Dark => "dark",
+            _ => "light",
+        }
+        .to_string(),
+        high_contrast: high_contrast(),
+        accent_color: accent_color(),
+    }
+}
+
+// Re-read the appearance and tell every window when it changed. Reading it may run
+// external tools, so it is done off the event loop.
+pub fn check_for_changes(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let appearance = current_appearance(&app);
+        let changed = match LAST_APPEARANCE.lock() {
+            Ok(mut last) => {
+                let changed = last.as_ref() != Some(&appearance);
+                *last = Some(appearance.clone());
+                changed
+            }
+            Err(_) => false,
+        };
+        if !changed {
+            return;
+        }
+
+        info!("System appearance changed: {:?}", appearance);
+        if let Err(e) = app.emit("appearance-changed", &appearance) {
+            warn!("Failed to emit appearance-changed: {}", e);
+        }
+        #[cfg(desktop)]
+        crate::tray::refresh(&app);
+    });
+}
+
+// Accent color and contrast changes have no event of their own, they are picked up when
+// the user comes back to the app
+pub fn check_on_focus(app: &AppHandle) {
+    let due = match LAST_FOCUS_CHECK.lock() {
+        Ok(mut last) => {
+            let due = !last.is_some_and(|t| t.elapsed() < FOCUS_CHECK_INTERVAL);
+            if due {
+                *last = Some(Instant::now());
+            }
+            due
+        }
+        Err(_) => false,
+    };
+    if due {
+        check_for_changes(app);
+    }
+}
+
+#[tauri::command]
+pub async fn get_system_appearance(app: AppHandle) -> SystemAppearance {
+    let appearance = current_appearance(&app);
+    if let Ok(mut last) = LAST_APPEARANCE.lock() {
+        *last = Some(appearance.clone());
+    }
+    appearance
+}
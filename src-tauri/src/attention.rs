@@ -0,0 +1,51 @@
+use log::info;
+use serde::Deserialize;
+use tauri::{AppHandle, Manager, UserAttentionType, WebviewWindow};
+
+use crate::notifications;
+
+// Event that wants the user's attention while the app is in the background
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AttentionKind {
+    IncomingCall,
+    Mention,
+    Message,
+}
+
+impl AttentionKind {
+    // Critical flashes until the window is focused (bounces the dock repeatedly on macOS),
+    // informational flashes briefly
+    fn attention_type(self) -> UserAttentionType {
+        match self {
+            AttentionKind::IncomingCall => UserAttentionType::Critical,
+            AttentionKind::Mention | AttentionKind::Message => UserAttentionType::Informational,
+        }
+    }
+}
+
+// Stop flashing once the user has come back to the window
+pub fn clear(window: &WebviewWindow) {
+    let _ = window.request_user_attention(None);
+}
+
+// Flash the taskbar button or bounce the dock icon of the main window. Returns whether
+// attention was requested: nothing happens while the window is focused or do not disturb is on.
+#[tauri::command]
+pub fn request_attention(app: AppHandle, kind: AttentionKind) -> Result<bool, String> {
+    if notifications::is_do_not_disturb() {
+        return Ok(false);
+    }
+    let Some(window) = app.get_webview_window("main") else {
+        return Ok(false);
+    };
+    if window.is_focused().unwrap_or(false) {
+        return Ok(false);
+    }
+
+    info!("Requesting user attention: {:?}", kind);
+    window
+        .request_user_attention(Some(kind.attention_type()))
+        .map_err(|e| format!("Failed to request attention: {}", e))?;
+    Ok(true)
+}
@@ -14,7 +14,7 @@ mod audio_impl {
     // A simple audio player using rodio
     // Uses a global sink manager to keep track of audio playback
     lazy_static::lazy_static! {
-        static ref AUDIO_PLAYERS: Mutex<HashMap<String, Arc<Mutex<Option<Sink>>>>> = Mutex::new(HashMap::new());
+        static ref AUDIO_PLAYERS: Mutex<HashMap<String, Arc<Sink>>> = Mutex::new(HashMap::new());
     }
 
     // Play an audio file with a given ID
@@ -40,18 +40,14 @@ mod audio_impl {
 
     // Stop playback for a specific ID
     pub fn stop_audio(id: String) -> Result<(), String> {
-        let players = AUDIO_PLAYERS
+        let mut players = AUDIO_PLAYERS
             .lock()
             .map_err(|e| format!("Failed to lock audio players: {}", e))?;
         info!("stop_audio: {}", id.clone());
 
-        if let Some(sink_mutex) = players.get(&id) {
-            if let Ok(mut sink_opt) = sink_mutex.lock() {
-                if let Some(sink) = sink_opt.take() {
-                    sink.stop();
-                    info!("Stopped audio: {}", id);
-                }
-            }
+        if let Some(sink) = players.remove(&id) {
+            sink.stop();
+            info!("Stopped audio: {}", id);
         }
 
         Ok(())
@@ -63,13 +59,26 @@ mod audio_impl {
             .lock()
             .map_err(|e| format!("Failed to lock audio players: {}", e))?;
 
-        if let Some(sink_mutex) = players.get(&id) {
-            if let Ok(sink_opt) = sink_mutex.lock() {
-                return Ok(sink_opt.is_some());
+        Ok(players.contains_key(&id))
+    }
+
+    // Number of sinks that are playing or paused
+    pub fn active_sinks() -> usize {
+        AUDIO_PLAYERS.lock().map(|players| players.len()).unwrap_or(0)
+    }
+
+    // Pause or resume everything that is playing, used while the system is suspended
+    pub fn set_all_paused(paused: bool) {
+        if let Ok(players) = AUDIO_PLAYERS.lock() {
+            for (id, sink) in players.iter() {
+                if paused {
+                    sink.pause();
+                } else {
+                    sink.play();
+                }
+                info!("Audio {} {}", id, if paused { "paused" } else { "resumed" });
             }
         }
-
-        Ok(false)
     }
 
     fn play_audio_internal(file_path: &str, id: &str) -> Result<(), String> {
@@ -91,29 +100,21 @@ mod audio_impl {
         // Add the source to the sink
         sink.append(source);
 
-        // Store the sink
-        {
-            let mut players = AUDIO_PLAYERS
-                .lock()
-                .map_err(|e| format!("Failed to lock audio players: {}", e))?;
-
-            let sink_mutex = Arc::new(Mutex::new(Some(sink)));
-            players.insert(id.to_string(), sink_mutex.clone());
-
-            // Get the sink back
-            let sink_clone = {
-                let mut sink_opt = sink_mutex
-                    .lock()
-                    .map_err(|e| format!("Failed to lock sink: {}", e))?;
-                sink_opt.take()
-            };
-
-            // Wait for playback to complete
-            if let Some(sink) = sink_clone {
-                sink.sleep_until_end();
-            }
+        // Store the sink. The lock is not held during playback so it can be stopped or paused.
+        let sink = Arc::new(sink);
+        AUDIO_PLAYERS
+            .lock()
+            .map_err(|e| format!("Failed to lock audio players: {}", e))?
+            .insert(id.to_string(), sink.clone());
+
+        // Wait for playback to complete
+        sink.sleep_until_end();
 
-            // Remove the sink from the map
+        // Remove the sink from the map, unless it was replaced by a newer playback with the same ID
+        let mut players = AUDIO_PLAYERS
+            .lock()
+            .map_err(|e| format!("Failed to lock audio players: {}", e))?;
+        if players.get(id).is_some_and(|s| Arc::ptr_eq(s, &sink)) {
             players.remove(id);
         }
 
@@ -140,6 +141,10 @@ mod audio_impl {
     pub fn is_audio_playing(_id: String) -> Result<bool, String> {
         Ok(false)
     }
+
+    pub fn active_sinks() -> usize {
+        0
+    }
 }
 
 // Expose commands using the platform-specific implementations
@@ -153,6 +158,15 @@ pub fn stop_audio(id: String) -> Result<(), String> {
     audio_impl::stop_audio(id)
 }
 
+#[cfg(desktop)]
+pub fn set_all_paused(paused: bool) {
+    audio_impl::set_all_paused(paused)
+}
+
+pub fn active_sinks() -> usize {
+    audio_impl::active_sinks()
+}
+
 #[tauri::command]
 pub fn is_audio_playing(id: String) -> Result<bool, String> {
     audio_impl::is_audio_playing(id)
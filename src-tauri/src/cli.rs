@@ -0,0 +1,159 @@
+use serde::Serialize;
+
+// Options parsed from the command line of the desktop binary
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchOptions {
+    pub minimized: bool,
+    pub show: bool,
+    pub profile: Option<String>,
+    pub log_level: Option<String>,
+    pub devtools: bool,
+    pub safe_mode: bool,
+    pub reset_window_state: bool,
+    pub uri: Option<String>,
+    pub files: Vec<String>,
+}
+
+pub fn help_text() -> String {
+    format!(
+        "Yellow {}
+
+Usage: yellow [OPTIONS] [yellow://URI | FILE...]
+
+Options:
+      --minimized            Start hidden in the system tray
+      --show                 Bring the running instance to the front
+      --profile <NAME>       Use a separate profile with its own data directory
+      --log-level <FILTER>   Log filter, e.g. \"debug\" or \"info,tauri_app_lib::audio=trace\"
+      --devtools             Open the webview developer tools
+      --safe-mode            Start with default window state and optional features disabled
+      --reset-window-state   Forget saved window sizes and positions
+  -h, --help                 Print help
+  -V, --version              Print version
+",
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
+// Parse arguments, without the program name
+pub fn parse<I, S>(args: I) -> Result<LaunchOptions, String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut options = LaunchOptions::default();
+    let mut args = args.into_iter().map(|a| a.as_ref().to_string());
+
+    while let Some(arg) = args.next() {
+        // Options may be given as `--name value` or `--name=value`
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = |name: &str| -> Result<String, String> {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| format!("option '{}' requires a value", name))
+        };
+
+        match name.as_str() {
+            "--minimized" => options.minimized = true,
+            "--show" => options.show = true,
+            "--devtools" => options.devtools = true,
+            "--safe-mode" => options.safe_mode = true,
+            "--reset-window-state" => options.reset_window_state = true,
+            "--profile" => options.profile = Some(value("--profile")?),
+            "--log-level" => {
+                let filter = value("--log-level")?;
+                validate_log_filter(&filter)?;
+                options.log_level = Some(filter);
+            }
+            // macOS passes a process serial number to apps started from Finder
+            _ if name.starts_with("-psn_") => {}
+            _ if name.starts_with('-') => return Err(format!("unexpected argument '{}'", arg)),
+            _ if arg.starts_with("yellow://") => {
+                if options.uri.is_some() {
+                    return Err("only one yellow:// URI may be given".to_string());
+                }
+                options.uri = Some(arg);
+            }
+            _ => options.files.push(arg),
+        }
+    }
+
+    Ok(options)
+}
+
+// Parse the process arguments. Prints help and exits on --help, --version or a parsing error.
+pub fn parse_or_exit() -> LaunchOptions {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        print!("{}", help_text());
+        std::process::exit(0);
+    }
+    if args.iter().any(|a| a == "-V" || a == "--version") {
+        println!("Yellow {}", env!("CARGO_PKG_VERSION"));
+        std::process::exit(0);
+    }
+
+    match parse(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, help_text());
+            std::process::exit(2);
+        }
+    }
+}
+
+// Accepts a level or a comma separated list of `level` and `module=level` directives
+fn validate_log_filter(filter: &str) -> Result<(), String> {
+    crate::logging::parse_filter(filter).map(|_| ())
+}
+
+#[tauri::command]
+pub fn get_launch_options(options: tauri::State<'_, LaunchOptions>) -> LaunchOptions {
+    options.inner().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_flags_and_values() {
+        let options = parse([
+            "--minimized",
+            "--profile",
+            "work",
+            "--log-level=debug,tauri_app_lib::audio=trace",
+            "--safe-mode",
+            "-psn_0_12345",
+        ])
+        .unwrap();
+        assert!(options.minimized);
+        assert!(options.safe_mode);
+        assert!(!options.show);
+        assert_eq!(options.profile.as_deref(), Some("work"));
+        assert_eq!(options.log_level.as_deref(), Some("debug,tauri_app_lib::audio=trace"));
+    }
+
+    #[test]
+    fn separates_uri_from_files() {
+        let options = parse(["notes.txt", "yellow://contact/bob@example.com", "photo.png"]).unwrap();
+        assert_eq!(options.uri.as_deref(), Some("yellow://contact/bob@example.com"));
+        assert_eq!(options.files, ["notes.txt", "photo.png"]);
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(parse(["--unknown"]).is_err());
+        assert!(parse(["--profile"]).is_err());
+        assert!(parse(["--profile="]).is_err());
+        assert!(parse(["--log-level", "loud"]).is_err());
+        assert!(parse(["yellow://contact/a@b", "yellow://contact/c@d"]).is_err());
+    }
+}
@@ -1,11 +1,20 @@
 use log::info;
 #[cfg(desktop)]
 use monitor_work_area::{get_work_area_tauri, Area};
-use tauri::Window;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::cli::LaunchOptions;
+use crate::frontend_log::{self, LogEntry};
 
 const GIT_HASH: &str = env!("GIT_HASH");
 const GIT_BRANCH: &str = env!("GIT_BRANCH");
 const BUILD_TIME: &str = env!("BUILD_TIME");
+// "true", "false" or "unknown" when built without git
+const GIT_DIRTY: &str = env!("GIT_DIRTY");
+const RUSTC_VERSION: &str = env!("RUSTC_VERSION");
+const BUILD_TARGET: &str = env!("BUILD_TARGET");
+const BUILD_PROFILE: &str = env!("BUILD_PROFILE");
 // Command to get window size
 #[tauri::command]
 pub fn get_window_size(window: Window) -> Result<(u32, u32), String> {
@@ -35,9 +44,24 @@ pub fn get_scale_factor(window: Window) -> Result<f64, String> {
     }
 }
 
+// Called by the main window once its event listeners are installed
+#[tauri::command]
+pub fn frontend_ready(app: tauri::AppHandle) {
+    info!("Frontend ready");
+    crate::metrics::mark(crate::metrics::Phase::FrontendReady);
+    #[cfg(desktop)]
+    crate::startup::complete();
+    crate::deeplink::set_frontend_ready(&app);
+}
+
+// Log from the frontend: a plain `message` logged at info, or a batch of structured `entries`
 #[tauri::command]
-pub fn log(message: String) {
-    info!("YELLOW {}", message);
+pub fn log(window: Window, message: Option<String>, entries: Option<Vec<LogEntry>>) {
+    let mut entries = entries.unwrap_or_default();
+    if let Some(message) = message {
+        entries.push(LogEntry::info(message));
+    }
+    frontend_log::log_entries(window.label(), entries);
 }
 
 
@@ -65,7 +89,92 @@ pub fn get_build_ts() -> String {
     BUILD_TIME.to_string()
 }
 
+// Plugins registered by run(), the window state plugin is skipped when saved state is ignored
+pub fn enabled_plugins(options: &LaunchOptions) -> Vec<&'static str> {
+    let mut plugins = vec![
+        "os",
+        "process",
+        "fs",
+        "dialog",
+        "notification",
+        "opener",
+        "deep-link",
+    ];
+    #[cfg(desktop)]
+    {
+        if crate::crash_reporting::is_active() {
+            plugins.push("sentry");
+        }
+        plugins.push("positioner");
+        if !options.reset_window_state && !options.safe_mode {
+            plugins.push("window-state");
+        }
+        plugins.extend(["store", "single-instance", "autostart", "updater", "global-shortcut"]);
+    }
+    #[cfg(mobile)]
+    {
+        let _ = options;
+        plugins.push("yellow");
+    }
+    plugins
+}
+
 #[tauri::command]
 pub fn is_debug_mode() -> bool {
     cfg!(debug_assertions)
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildInfo {
+    pub version: String,
+    pub git_hash: &'static str,
+    pub git_branch: &'static str,
+    // None when the build had no git checkout
+    pub dirty: Option<bool>,
+    pub build_time: &'static str,
+    // Cargo profile, "debug" or "release"
+    pub profile: &'static str,
+    pub debug: bool,
+    pub target: &'static str,
+    pub rustc_version: &'static str,
+    pub features: Vec<&'static str>,
+    pub plugins: Vec<&'static str>,
+}
+
+// Cargo features of this crate compiled in
+fn enabled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "sentry") {
+        features.push("sentry");
+    }
+    if cfg!(feature = "devtools") {
+        features.push("devtools");
+    }
+    if cfg!(feature = "manual_cxx_lib") {
+        features.push("manual_cxx_lib");
+    }
+    features
+}
+
+pub fn build_info(app: &AppHandle) -> BuildInfo {
+    BuildInfo {
+        version: app.package_info().version.to_string(),
+        git_hash: GIT_HASH,
+        git_branch: GIT_BRANCH,
+        dirty: GIT_DIRTY.parse().ok(),
+        build_time: BUILD_TIME,
+        profile: BUILD_PROFILE,
+        debug: cfg!(debug_assertions),
+        target: BUILD_TARGET,
+        rustc_version: RUSTC_VERSION,
+        features: enabled_features(),
+        plugins: enabled_plugins(app.state::<LaunchOptions>().inner()),
+    }
+}
+
+// Everything about this build in one call, for the About dialog and bug reports
+#[tauri::command]
+pub fn get_build_info(app: AppHandle) -> BuildInfo {
+    build_info(&app)
 }
\ No newline at end of file
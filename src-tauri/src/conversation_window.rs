@@ -0,0 +1,323 @@
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent};
+
+use crate::misc;
+
+const STATE_FILE: &str = "conversation-windows.json";
+const LABEL_PREFIX: &str = "conversation-";
+// Geometry of closed pop-outs is kept for the most recently used conversations only
+const MAX_SAVED_WINDOWS: usize = 50;
+
+// Labels are reused across starts, so these windows keep their geometry in STATE_FILE
+// instead of the window state plugin
+pub fn is_conversation_window(label: &str) -> bool {
+    label.starts_with(LABEL_PREFIX)
+}
+
+// Saved geometry of a pop-out conversation window, in logical pixels
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedWindow {
+    account: String,
+    conversation: String,
+    x: Option<f64>,
+    y: Option<f64>,
+    width: f64,
+    height: f64,
+    // Still open when the app exited, reopened on the next start
+    open: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationWindowInfo {
+    pub label: String,
+    pub account: String,
+    pub conversation: String,
+}
+
+lazy_static::lazy_static! {
+    // label -> (account, conversation) of the open pop-outs
+    static ref OPEN_WINDOWS: Mutex<HashMap<String, (String, String)>> = Mutex::new(HashMap::new());
+    static ref SAVED_WINDOWS: Mutex<Vec<SavedWindow>> = Mutex::new(Vec::new());
+}
+
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+fn state_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(STATE_FILE))
+}
+
+fn load_state(app: &AppHandle) {
+    let saved = state_path(app)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str::<Vec<SavedWindow>>(&text).ok())
+        .unwrap_or_default();
+    if let Ok(mut windows) = SAVED_WINDOWS.lock() {
+        *windows = saved;
+    }
+}
+
+fn save_state(app: &AppHandle) {
+    let Some(path) = state_path(app) else {
+        return;
+    };
+    let text = match SAVED_WINDOWS.lock() {
+        Ok(windows) => serde_json::to_string_pretty(&*windows).unwrap_or_default(),
+        Err(_) => return,
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Err(e) = fs::write(&path, text) {
+        warn!("Failed to save conversation windows: {}", e);
+    }
+}
+
+// Drop the least recently used closed entries beyond MAX_SAVED_WINDOWS
+fn prune(windows: &mut Vec<SavedWindow>) {
+    let mut excess = windows.len().saturating_sub(MAX_SAVED_WINDOWS);
+    windows.retain(|w| {
+        if excess > 0 && !w.open {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+// Find or create the saved entry of a conversation, move it to the end as the most recently
+// used one and apply a change to it
+fn update_saved<F: FnOnce(&mut SavedWindow)>(account: &str, conversation: &str, f: F) {
+    if let Ok(mut windows) = SAVED_WINDOWS.lock() {
+        let mut entry = match windows
+            .iter()
+            .position(|w| w.account == account && w.conversation == conversation)
+        {
+            Some(index) => windows.remove(index),
+            None => SavedWindow {
+                account: account.to_string(),
+                conversation: conversation.to_string(),
+                x: None,
+                y: None,
+                width: 600.0,
+                height: 700.0,
+                open: false,
+            },
+        };
+        f(&mut entry);
+        windows.push(entry);
+        prune(&mut windows);
+    }
+}
+
+fn find_open(account: &str, conversation: &str) -> Option<String> {
+    OPEN_WINDOWS.lock().ok().and_then(|windows| {
+        windows
+            .iter()
+            .find(|(_, (a, c))| a == account && c == conversation)
+            .map(|(label, _)| label.clone())
+    })
+}
+
+fn open_window(app: &AppHandle, account: &str, conversation: &str) -> Result<String, String> {
+    if let Some(label) = find_open(account, conversation) {
+        if let Some(window) = app.get_webview_window(&label) {
+            let _ = window.show();
+            let _ = window.unminimize();
+            let _ = window.set_focus();
+            return Ok(label);
+        }
+    }
+
+    let label = format!("{}{}", LABEL_PREFIX, NEXT_ID.fetch_add(1, Ordering::SeqCst));
+    info!("Opening conversation window {} for {} / {}", label, account, conversation);
+
+    let mut saved = None;
+    update_saved(account, conversation, |w| saved = Some(w.clone()));
+    let saved = saved.ok_or_else(|| "Failed to lock conversation windows".to_string())?;
+
+    let info = ConversationWindowInfo {
+        label: label.clone(),
+        account: account.to_string(),
+        conversation: conversation.to_string(),
+    };
+    let info_json = serde_json::to_string(&info)
+        .map_err(|e| format!("Failed to serialize conversation: {}", e))?;
+
+    let builder = WebviewWindowBuilder::new(app, &label, WebviewUrl::App("/conversation".into()))
+        .initialization_script(misc::get_error_handler_script())
+        .initialization_script(format!("window.__YELLOW_CONVERSATION__ = {};", info_json))
+        .initialization_script(crate::zoom::get_hotkey_script())
+        .title(format!("{} - Yellow", conversation))
+        .inner_size(saved.width, saved.height);
+    let builder = match (saved.x, saved.y) {
+        (Some(x), Some(y)) => builder.position(x, y),
+        _ => builder.center(),
+    };
+    let (builder, throttling) = crate::windows::apply_background_throttling(builder, &label);
+    let window = crate::profile::apply_webview_data(builder, app)
+        .build()
+        .map_err(|e| format!("Failed to create conversation window: {}", e))?;
+    crate::windows::record_background_throttling(&window, throttling);
+    crate::zoom::apply_saved_zoom(&window);
+
+    if let Ok(mut windows) = OPEN_WINDOWS.lock() {
+        windows.insert(label.clone(), (account.to_string(), conversation.to_string()));
+    }
+    update_saved(account, conversation, |w| w.open = true);
+    save_state(app);
+
+    let event_window = window.clone();
+    let event_info = info.clone();
+    window.on_window_event(move |event| handle_window_event(&event_window, &event_info, event));
+
+    if let Err(e) = app.emit_to("main", "conversation-window-opened", &info) {
+        warn!("Failed to emit conversation-window-opened: {}", e);
+    }
+    Ok(label)
+}
+
+fn handle_window_event(window: &WebviewWindow, info: &ConversationWindowInfo, event: &WindowEvent) {
+    let app = window.app_handle();
+    match event {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+            remember_geometry(window, info);
+        }
+        // Geometry is written to disk when the window loses focus or is closed by the user
+        WindowEvent::Focused(false) => {
+            save_state(app);
+        }
+        WindowEvent::CloseRequested { .. } => {
+            remember_geometry(window, info);
+            update_saved(&info.account, &info.conversation, |w| w.open = false);
+            save_state(app);
+        }
+        WindowEvent::Destroyed => {
+            if let Ok(mut windows) = OPEN_WINDOWS.lock() {
+                windows.remove(&info.label);
+            }
+            info!("Conversation window {} closed", info.label);
+            if let Err(e) = app.emit_to("main", "conversation-window-closed", info) {
+                warn!("Failed to emit conversation-window-closed: {}", e);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn remember_geometry(window: &WebviewWindow, info: &ConversationWindowInfo) {
+    // Minimized windows report meaningless positions
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let scale = window.scale_factor().unwrap_or(1.0);
+    let position = window.outer_position().ok().map(|p| p.to_logical::<f64>(scale));
+    let size = window.inner_size().ok().map(|s| s.to_logical::<f64>(scale));
+
+    update_saved(&info.account, &info.conversation, |w| {
+        if let Some(position) = position {
+            w.x = Some(position.x);
+            w.y = Some(position.y);
+        }
+        if let Some(size) = size {
+            w.width = size.width;
+            w.height = size.height;
+        }
+    });
+}
+
+// Reopen the pop-outs that were open when the app last exited
+pub fn restore_conversation_windows(app: &AppHandle) {
+    load_state(app);
+    let to_open: Vec<(String, String)> = SAVED_WINDOWS
+        .lock()
+        .map(|windows| {
+            windows
+                .iter()
+                .filter(|w| w.open)
+                .map(|w| (w.account.clone(), w.conversation.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    for (account, conversation) in to_open {
+        if let Err(e) = open_window(app, &account, &conversation) {
+            warn!("Failed to restore conversation window: {}", e);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn open_conversation_window(
+    app: AppHandle,
+    account: String,
+    conversation: String,
+) -> Result<String, String> {
+    open_window(&app, &account, &conversation)
+}
+
+#[tauri::command]
+pub fn close_conversation_window(
+    app: AppHandle,
+    account: String,
+    conversation: String,
+) -> Result<(), String> {
+    if let Some(window) = find_open(&account, &conversation).and_then(|l| app.get_webview_window(&l)) {
+        window
+            .close()
+            .map_err(|e| format!("Failed to close conversation window: {}", e))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_conversation_windows() -> Vec<ConversationWindowInfo> {
+    OPEN_WINDOWS
+        .lock()
+        .map(|windows| {
+            windows
+                .iter()
+                .map(|(label, (account, conversation))| ConversationWindowInfo {
+                    label: label.clone(),
+                    account: account.clone(),
+                    conversation: conversation.clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(conversation: &str, open: bool) -> SavedWindow {
+        SavedWindow {
+            account: "account".to_string(),
+            conversation: conversation.to_string(),
+            x: None,
+            y: None,
+            width: 600.0,
+            height: 700.0,
+            open,
+        }
+    }
+
+    #[test]
+    fn prune_drops_oldest_closed_windows() {
+        let mut windows: Vec<SavedWindow> = (0..MAX_SAVED_WINDOWS + 2)
+            .map(|i| saved(&i.to_string(), i == 0))
+            .collect();
+        prune(&mut windows);
+        assert_eq!(windows.len(), MAX_SAVED_WINDOWS);
+        assert_eq!(windows[0].conversation, "0");
+        assert_eq!(windows[1].conversation, "3");
+    }
+}
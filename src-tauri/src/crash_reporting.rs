@@ -0,0 +1,209 @@
+use log::info;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Wry};
+
+use crate::settings;
+
+// Overrides the DSN from settings and tauri.conf.json
+const DSN_ENV: &str = "YELLOW_SENTRY_DSN";
+// Set by the app process for the crash reporter process it starts, which has its own
+// arguments and must make the same decision
+const REPORTER_DSN_ENV: &str = "YELLOW_CRASH_REPORTER_DSN";
+// Section of the `plugins` object in tauri.conf.json
+const CONFIG_SECTION: &str = "crash-reporting";
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReportingStatus {
+    // None until the user answered the first-run question
+    pub consent: Option<bool>,
+    // Compiled in and a DSN is configured
+    pub available: bool,
+    // Reporting in this process, consent changes apply on the next start
+    pub active: bool,
+}
+
+// DSN from the environment, native settings or tauri.conf.json, in that order
+fn configured_dsn(config: &tauri::Config) -> Option<String> {
+    std::env::var(DSN_ENV)
+        .ok()
+        .or_else(|| settings::get().crash_reporting_dsn)
+        .or_else(|| {
+            config
+                .plugins
+                .0
+                .get(CONFIG_SECTION)
+                .and_then(|section| section.get("dsn"))
+                .and_then(|dsn| dsn.as_str())
+                .map(str::to_string)
+        })
+        .filter(|dsn| !dsn.trim().is_empty())
+}
+
+// Settings are normally loaded in setup, crash reporting has to be decided before the app is
+// built. The profile is peeked from the arguments, they are validated later.
+fn load_settings(context: &tauri::Context<Wry>) {
+    let profile = crate::cli::parse(std::env::args().skip(1))
+        .ok()
+        .and_then(|options| options.profile);
+    let name = profile.as_deref().unwrap_or(crate::profile::DEFAULT_PROFILE);
+    let identifier = crate::profile::identifier_for(&context.config().identifier, name);
+    if let Some(config_dir) = dirs::config_dir() {
+        settings::init(config_dir.join(identifier));
+    }
+}
+
+// The DSN to report to, None without consent or configuration
+pub fn dsn(context: &tauri::Context<Wry>) -> Option<String> {
+    if let Ok(dsn) = std::env::var(REPORTER_DSN_ENV) {
+        return Some(dsn);
+    }
+    if !cfg!(feature = "sentry") {
+        return None;
+    }
+    load_settings(context);
+    if settings::get().crash_reporting != Some(true) {
+        return None;
+    }
+    configured_dsn(context.config())
+}
+
+#[cfg(feature = "sentry")]
+mod reporting {
+    use std::sync::Arc;
+    use tauri_plugin_sentry::sentry;
+    use sentry::protocol::{Breadcrumb, DebugImage, Event, Stacktrace, Value};
+
+    use crate::scrub::scrub_pii;
+
+    fn scrub_value(value: &mut Value) {
+        match value {
+            Value::String(s) => *s = scrub_pii(s),
+            Value::Array(items) => items.iter_mut().for_each(scrub_value),
+            Value::Object(map) => map.values_mut().for_each(scrub_value),
+            _ => {}
+        }
+    }
+
+    fn scrub_breadcrumb(mut breadcrumb: Breadcrumb) -> Breadcrumb {
+        breadcrumb.message = breadcrumb.message.map(|m| scrub_pii(&m));
+        breadcrumb.data.values_mut().for_each(scrub_value);
+        breadcrumb
+    }
+
+    fn scrub_stacktrace(stacktrace: &mut Option<Stacktrace>) {
+        for frame in stacktrace.iter_mut().flat_map(|s| s.frames.iter_mut()) {
+            frame.abs_path = frame.abs_path.as_deref().map(scrub_pii);
+            frame.filename = frame.filename.as_deref().map(scrub_pii);
+        }
+    }
+
+    // Loaded libraries and the executable are reported with their install paths
+    fn scrub_debug_image(image: &mut DebugImage) {
+        match image {
+            DebugImage::Apple(image) => image.name = scrub_pii(&image.name),
+            DebugImage::Symbolic(image) => {
+                image.name = scrub_pii(&image.name);
+                image.debug_file = image.debug_file.as_deref().map(scrub_pii);
+            }
+            DebugImage::Wasm(image) => {
+                image.name = scrub_pii(&image.name);
+                image.code_file = scrub_pii(&image.code_file);
+                image.debug_file = image.debug_file.as_deref().map(scrub_pii);
+            }
+            _ => {}
+        }
+    }
+
+    // Strip paths, user names and server addresses before anything leaves the machine
+    fn scrub_event(mut event: Event<'static>) -> Event<'static> {
+        event.user = None;
+        event.server_name = None;
+        event.request = None;
+        event.message = event.message.map(|m| scrub_pii(&m));
+        event.logger = event.logger.map(|l| scrub_pii(&l));
+        event.extra.values_mut().for_each(scrub_value);
+        for value in event.tags.values_mut() {
+            *value = scrub_pii(value);
+        }
+        for exception in event.exception.values.iter_mut() {
+            exception.value = exception.value.as_deref().map(scrub_pii);
+            scrub_stacktrace(&mut exception.stacktrace);
+            scrub_stacktrace(&mut exception.raw_stacktrace);
+        }
+        // Filled in by attach_stacktrace
+        scrub_stacktrace(&mut event.stacktrace);
+        for thread in event.threads.values.iter_mut() {
+            scrub_stacktrace(&mut thread.stacktrace);
+            scrub_stacktrace(&mut thread.raw_stacktrace);
+        }
+        event.debug_meta.to_mut().images.iter_mut().for_each(scrub_debug_image);
+        let breadcrumbs = std::mem::take(&mut event.breadcrumbs.values);
+        event.breadcrumbs.values = breadcrumbs.into_iter().map(scrub_breadcrumb).collect();
+        event
+    }
+
+    pub fn init(dsn: &str) -> sentry::ClientInitGuard {
+        sentry::init((
+            dsn,
+            sentry::ClientOptions {
+                release: sentry::release_name!(),
+                auto_session_tracking: true,
+                attach_stacktrace: true,
+                trim_backtraces: false,
+                send_default_pii: false,
+                before_send: Some(Arc::new(|event| Some(scrub_event(event)))),
+                before_breadcrumb: Some(Arc::new(|breadcrumb| Some(scrub_breadcrumb(breadcrumb)))),
+                ..Default::default()
+            },
+        ))
+    }
+}
+
+// Start Sentry and the minidump crash reporter. In the crash reporter process this does not return.
+#[cfg(feature = "sentry")]
+pub fn init(
+    dsn: &str,
+) -> (
+    tauri_plugin_sentry::sentry::ClientInitGuard,
+    Option<tauri_plugin_sentry::minidump::Handle>,
+) {
+    std::env::set_var(REPORTER_DSN_ENV, dsn);
+    let client = reporting::init(dsn);
+    // Logging is not set up yet at this point
+    let minidump = tauri_plugin_sentry::minidump::init(&client)
+        .map_err(|e| eprintln!("Failed to start the crash reporter: {}", e))
+        .ok();
+    // Only the crash reporter may inherit it, not profiles or updates launched later
+    std::env::remove_var(REPORTER_DSN_ENV);
+    ACTIVE.store(true, Ordering::SeqCst);
+    (client, minidump)
+}
+
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::SeqCst)
+}
+
+fn status(app: &AppHandle) -> CrashReportingStatus {
+    CrashReportingStatus {
+        consent: settings::get().crash_reporting,
+        available: cfg!(feature = "sentry") && configured_dsn(app.config()).is_some(),
+        active: is_active(),
+    }
+}
+
+#[tauri::command]
+pub fn get_crash_reporting(app: AppHandle) -> CrashReportingStatus {
+    status(&app)
+}
+
+// Store the answer to the consent question, applied on the next start
+#[tauri::command]
+pub fn set_crash_reporting_consent(app: AppHandle, enabled: bool) -> Result<CrashReportingStatus, String> {
+    info!("Crash reporting consent: {}", enabled);
+    settings::update(|s| s.crash_reporting = Some(enabled))?;
+    Ok(status(&app))
+}
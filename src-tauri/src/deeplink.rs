@@ -0,0 +1,244 @@
+use log::{info, warn};
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Url};
+
+pub const SCHEME: &str = "yellow";
+
+// A validated yellow:// link
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DeepLink {
+    // yellow://invite?server=<server>&username=<username>
+    Invite { server: String, username: String },
+    // yellow://contact/<address>
+    Contact { address: String },
+    // yellow://conversation/<account>/<address>
+    Conversation { account: String, address: String },
+}
+
+lazy_static::lazy_static! {
+    static ref PENDING: Mutex<Vec<DeepLink>> = Mutex::new(Vec::new());
+}
+
+static FRONTEND_READY: AtomicBool = AtomicBool::new(false);
+
+pub fn parse(link: &str) -> Result<DeepLink, String> {
+    let url = Url::parse(link.trim()).map_err(|e| format!("Invalid URL: {}", e))?;
+    if url.scheme() != SCHEME {
+        return Err(format!("Unsupported scheme: {}", url.scheme()));
+    }
+
+    let segments: Vec<String> = url
+        .path_segments()
+        .map(|s| s.filter(|s| !s.is_empty()).map(|s| percent_decode_str(s).decode_utf8_lossy().into_owned()).collect())
+        .unwrap_or_default();
+
+    match url.host_str().unwrap_or_default() {
+        "invite" => {
+            let query = |key: &str| {
+                url.query_pairs()
+                    .find(|(k, _)| k == key)
+                    .map(|(_, v)| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+                    .ok_or_else(|| format!("Invite link is missing '{}'", key))
+            };
+            let server = query("server")?;
+            let username = query("username")?;
+            validate_server(&server)?;
+            validate_username(&username)?;
+            Ok(DeepLink::Invite { server, username })
+        }
+        "contact" => match segments.as_slice() {
+            [address] => {
+                validate_address(address)?;
+                Ok(DeepLink::Contact {
+                    address: address.clone(),
+                })
+            }
+            _ => Err("Contact link must be yellow://contact/<address>".to_string()),
+        },
+        "conversation" => match segments.as_slice() {
+            [account, address] => {
+                validate_address(account)?;
+                validate_address(address)?;
+                Ok(DeepLink::Conversation {
+                    account: account.clone(),
+                    address: address.clone(),
+                })
+            }
+            _ => Err("Conversation link must be yellow://conversation/<account>/<address>".to_string()),
+        },
+        other => Err(format!("Unknown link type: {}", other)),
+    }
+}
+
+// Parse a link and deliver it to the frontend, or queue it until the frontend is ready
+pub fn handle_url(app: &AppHandle, link: &str) {
+    let deep_link = match parse(link) {
+        Ok(deep_link) => deep_link,
+        Err(e) => {
+            warn!("Ignoring deep link {}: {}", link, e);
+            return;
+        }
+    };
+    info!("Deep link: {:?}", deep_link);
+
+    if FRONTEND_READY.load(Ordering::SeqCst) {
+        emit(app, &deep_link);
+    } else if let Ok(mut pending) = PENDING.lock() {
+        pending.push(deep_link);
+    }
+}
+
+// Called once the main window has its listeners installed, delivers the queued links
+pub fn set_frontend_ready(app: &AppHandle) {
+    FRONTEND_READY.store(true, Ordering::SeqCst);
+    let pending: Vec<DeepLink> = PENDING
+        .lock()
+        .map(|mut p| p.drain(..).collect())
+        .unwrap_or_default();
+    for deep_link in &pending {
+        emit(app, deep_link);
+    }
+}
+
+// The main window is gone, queue links until the next frontend signals readiness
+pub fn reset_frontend_ready() {
+    FRONTEND_READY.store(false, Ordering::SeqCst);
+}
+
+fn emit(app: &AppHandle, deep_link: &DeepLink) {
+    if let Err(e) = app.emit_to("main", "deep-link", deep_link) {
+        warn!("Failed to emit deep-link event: {}", e);
+    }
+}
+
+fn validate_address(address: &str) -> Result<(), String> {
+    let valid = match address.split_once('@') {
+        Some((user, domain)) => {
+            !user.is_empty()
+                && !domain.is_empty()
+                && !domain.contains('@')
+                && address.len() <= 320
+                && !address.chars().any(|c| c.is_whitespace() || c.is_control() || c == '/')
+        }
+        None => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid address: {}", address))
+    }
+}
+
+fn validate_username(username: &str) -> Result<(), String> {
+    let valid = username.len() <= 64
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid username: {}", username))
+    }
+}
+
+// Servers are given either as a host name or as a ws(s)/http(s) URL
+fn validate_server(server: &str) -> Result<(), String> {
+    let with_scheme = if server.contains("://") {
+        server.to_string()
+    } else {
+        format!("wss://{}", server)
+    };
+    match Url::parse(&with_scheme) {
+        Ok(url)
+            if matches!(url.scheme(), "ws" | "wss" | "http" | "https")
+                && url.host_str().is_some_and(|h| !h.is_empty()) =>
+        {
+            Ok(())
+        }
+        _ => Err(format!("Invalid server: {}", server)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_invite() {
+        assert_eq!(
+            parse("yellow://invite?server=example.com&username=alice"),
+            Ok(DeepLink::Invite {
+                server: "example.com".to_string(),
+                username: "alice".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("yellow://invite?server=wss%3A%2F%2Fchat.example.com%2Fws&username=alice"),
+            Ok(DeepLink::Invite {
+                server: "wss://chat.example.com/ws".to_string(),
+                username: "alice".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_contact_and_conversation() {
+        assert_eq!(
+            parse("yellow://contact/bob%40example.com/"),
+            Ok(DeepLink::Contact {
+                address: "bob@example.com".to_string(),
+            })
+        );
+        assert_eq!(
+            parse(" yellow://conversation/alice@example.com/bob@example.com "),
+            Ok(DeepLink::Conversation {
+                account: "alice@example.com".to_string(),
+                address: "bob@example.com".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_links() {
+        for link in [
+            "not a url",
+            "https://contact/bob@example.com",
+            "yellow://contact/",
+            "yellow://contact/bob@example.com/extra",
+            "yellow://contact/bob",
+            "yellow://conversation/alice@example.com",
+            "yellow://invite?server=example.com",
+            "yellow://invite?server=example.com&username=",
+            "yellow://invite?server=ftp%3A%2F%2Fexample.com&username=alice",
+            "yellow://invite?server=example.com&username=al%20ice",
+            "yellow://settings",
+        ] {
+            assert!(parse(link).is_err(), "{}", link);
+        }
+    }
+
+    #[test]
+    fn validates_servers() {
+        for server in ["example.com", "example.com:8084", "wss://example.com/ws", "https://example.com"] {
+            assert_eq!(validate_server(server), Ok(()), "{}", server);
+        }
+        for server in ["", "wss://", "ftp://example.com", "exa mple.com"] {
+            assert!(validate_server(server).is_err(), "{}", server);
+        }
+    }
+
+    #[test]
+    fn validates_addresses() {
+        assert_eq!(validate_address("alice@example.com"), Ok(()));
+        for address in ["alice", "@example.com", "alice@", "a@b@c", "al ice@example.com", "a/b@example.com"] {
+            assert!(validate_address(address).is_err(), "{}", address);
+        }
+        let long = format!("{}@example.com", "a".repeat(320));
+        assert!(validate_address(&long).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use chrono::Utc;
+use log::{info, warn};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{Seek, Write};
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::cli::LaunchOptions;
+use crate::{commands, logging, scrub, settings};
+
+fn system_info(app: &AppHandle) -> Value {
+    let monitors: Vec<Value> = app
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| {
+            json!({
+                "name": monitor.name(),
+                "size": [monitor.size().width, monitor.size().height],
+                "position": [monitor.position().x, monitor.position().y],
+                "scaleFactor": monitor.scale_factor(),
+            })
+        })
+        .collect();
+
+    let windows: Vec<Value> = app
+        .webview_windows()
+        .into_iter()
+        .map(|(label, window)| {
+            let window = window.as_ref().window();
+            json!({
+                "label": label,
+                "size": commands::get_window_size(window.clone()).ok(),
+                "scaleFactor": commands::get_scale_factor(window.clone()).ok(),
+                "visible": window.is_visible().ok(),
+            })
+        })
+        .collect();
+
+    json!({
+        "platform": tauri_plugin_os::platform().to_string(),
+        "osType": tauri_plugin_os::type_().to_string(),
+        "osVersion": tauri_plugin_os::version().to_string(),
+        "family": tauri_plugin_os::family().to_string(),
+        "arch": tauri_plugin_os::arch().to_string(),
+        "locale": tauri_plugin_os::locale(),
+        "monitors": monitors,
+        "windows": windows,
+    })
+}
+
+fn redacted_settings(options: &LaunchOptions) -> Value {
+    let mut native = serde_json::to_value(settings::get()).unwrap_or(Value::Null);
+    scrub::redact_json(&mut native);
+
+    // Links and file paths from the command line can name accounts and servers
+    let mut launch = serde_json::to_value(options).unwrap_or(Value::Null);
+    if let Some(launch) = launch.as_object_mut() {
+        for key in ["uri", "files"] {
+            if launch.get(key).is_some_and(|v| !v.is_null()) {
+                launch.insert(key.to_string(), json!("[redacted]"));
+            }
+        }
+    }
+
+    json!({
+        "native": native,
+        "launchOptions": launch,
+    })
+}
+
+fn add_file<W: Write + Seek>(zip: &mut ZipWriter<W>, name: &str, data: &[u8]) -> Result<(), String> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file(name, options)
+        .map_err(|e| format!("Failed to add {} to bundle: {}", name, e))?;
+    zip.write_all(data)
+        .map_err(|e| format!("Failed to write {} to bundle: {}", name, e))
+}
+
+fn add_json<W: Write + Seek>(zip: &mut ZipWriter<W>, name: &str, value: &Value) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", name, e))?;
+    add_file(zip, name, text.as_bytes())
+}
+
+fn write_bundle<W: Write + Seek>(app: &AppHandle, writer: W) -> Result<W, String> {
+    let options = app.state::<LaunchOptions>().inner().clone();
+    let mut zip = ZipWriter::new(writer);
+
+    add_json(&mut zip, "build-info.json", &json!(commands::build_info(app)))?;
+    add_json(&mut zip, "system.json", &system_info(app))?;
+    add_json(&mut zip, "plugins.json", &json!(commands::enabled_plugins(&options)))?;
+    add_json(&mut zip, "settings.json", &redacted_settings(&options))?;
+
+    // Logs can contain secrets from key=value pairs and message bodies from the frontend
+    for path in logging::log_files() {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let text = match fs::read(&path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) => {
+                warn!("Failed to read {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        add_file(&mut zip, &format!("logs/{}", name), scrub::scrub_log(&text).as_bytes())?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finish diagnostics bundle: {}", e))
+}
+
+fn bundle_name() -> String {
+    format!("yellow-diagnostics-{}.zip", Utc::now().format("%Y%m%d-%H%M%S"))
+}
+
+// Write a zip with logs, build, system and plugin info and redacted settings to the downloads
+// directory, or the log directory without one. Returns the path of the bundle.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn create_diagnostics_bundle(app: AppHandle) -> Result<String, String> {
+    let path = app
+        .path()
+        .download_dir()
+        .or_else(|_| app.path().app_log_dir())
+        .map_err(|e| format!("Failed to resolve downloads directory: {}", e))?
+        .join(bundle_name());
+    info!("Creating diagnostics bundle: {}", path.display());
+
+    let file = fs::File::create(&path)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    write_bundle(&app, file)?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+// On mobile the bundle is handed to the platform's downloads through the yellow plugin.
+// Returns the file name.
+#[cfg(mobile)]
+#[tauri::command]
+pub async fn create_diagnostics_bundle(app: AppHandle) -> Result<String, String> {
+    use base64::Engine;
+    use tauri_plugin_yellow::YellowExt;
+
+    let name = bundle_name();
+    info!("Creating diagnostics bundle: {}", name);
+
+    let buffer = write_bundle(&app, std::io::Cursor::new(Vec::new()))?.into_inner();
+    let data = base64::engine::general_purpose::STANDARD.encode(buffer);
+    app.yellow()
+        .save_to_downloads(name.clone(), "application/zip".to_string(), data)
+        .map_err(|e| format!("Failed to save diagnostics bundle: {}", e))?;
+    Ok(name)
+}
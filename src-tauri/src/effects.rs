@@ -0,0 +1,157 @@
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::settings::{self, WindowEffect};
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowEffectSupport {
+    pub platform: String,
+    pub os_version: String,
+    pub supported: Vec<WindowEffect>,
+}
+
+// Windows build number, e.g. 22631 for "10.0.22631"
+#[cfg(target_os = "windows")]
+fn windows_build() -> u32 {
+    tauri_plugin_os::version()
+        .to_string()
+        .split('.')
+        .nth(2)
+        .and_then(|build| build.parse().ok())
+        .unwrap_or(0)
+}
+
+pub fn supported_effects() -> Vec<WindowEffect> {
+    #[allow(unused_mut)]
+    let mut effects = vec![WindowEffect::None];
+
+    #[cfg(target_os = "windows")]
+    {
+        let build = windows_build();
+        effects.push(WindowEffect::Blur);
+        // Acrylic needs Windows 10 1809, mica Windows 11
+        if build >= 17763 {
+            effects.push(WindowEffect::Acrylic);
+        }
+        if build >= 22000 {
+            effects.push(WindowEffect::Mica);
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    effects.push(WindowEffect::Vibrancy);
+
+    effects
+}
+
+fn clear_effects(window: &WebviewWindow) {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = window_vibrancy::clear_blur(window);
+        let _ = window_vibrancy::clear_acrylic(window);
+        let _ = window_vibrancy::clear_mica(window);
+    }
+
+    #[cfg(target_os = "macos")]
+    let _ = window_vibrancy::clear_vibrancy(window);
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let _ = window;
+}
+
+fn apply_on_main_thread(window: &WebviewWindow, effect: WindowEffect) -> Result<(), String> {
+    clear_effects(window);
+
+    let result = match effect {
+        WindowEffect::None => Ok(()),
+        WindowEffect::Blur => window_vibrancy::apply_blur(window, None),
+        WindowEffect::Acrylic => window_vibrancy::apply_acrylic(window, None),
+        WindowEffect::Mica => window_vibrancy::apply_mica(window, None),
+        #[cfg(target_os = "macos")]
+        WindowEffect::Vibrancy => window_vibrancy::apply_vibrancy(
+            window,
+            window_vibrancy::NSVisualEffectMaterial::HudWindow,
+            None,
+            None,
+        ),
+        #[cfg(not(target_os = "macos"))]
+        WindowEffect::Vibrancy => Err(window_vibrancy::Error::UnsupportedPlatform(
+            "vibrancy is only supported on macOS",
+        )),
+    };
+    result.map_err(|e| format!("Failed to apply {:?} to {}: {}", effect, window.label(), e))
+}
+
+// Apply an effect to a window. The native calls have to run on the main thread,
+// so failures there are only logged.
+pub fn apply_effect(window: &WebviewWindow, effect: WindowEffect) -> Result<(), String> {
+    if !supported_effects().contains(&effect) {
+        return Err(format!("Window effect {:?} is not supported on this system", effect));
+    }
+
+    info!("Applying window effect {:?} to {}", effect, window.label());
+    let target = window.clone();
+    window
+        .run_on_main_thread(move || {
+            if let Err(e) = apply_on_main_thread(&target, effect) {
+                warn!("{}", e);
+            }
+        })
+        .map_err(|e| format!("Failed to apply window effect: {}", e))
+}
+
+// Reapply the persisted effect to a newly created window
+pub fn apply_saved_effect(window: &WebviewWindow) {
+    let effect = settings::get()
+        .window_effects
+        .get(window.label())
+        .copied()
+        .unwrap_or_default();
+    if effect != WindowEffect::None {
+        if let Err(e) = apply_effect(window, effect) {
+            warn!("{}", e);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_window_effect_support() -> WindowEffectSupport {
+    WindowEffectSupport {
+        platform: tauri_plugin_os::platform().to_string(),
+        os_version: tauri_plugin_os::version().to_string(),
+        supported: supported_effects(),
+    }
+}
+
+#[tauri::command]
+pub fn get_window_effect(window: String) -> WindowEffect {
+    settings::get()
+        .window_effects
+        .get(&window)
+        .copied()
+        .unwrap_or_default()
+}
+
+// Set and persist the effect of a window by label. The effect is applied right away
+// when the window is open and again whenever it is created.
+#[tauri::command]
+pub fn set_window_effect(app: AppHandle, window: String, effect: WindowEffect) -> Result<(), String> {
+    if !supported_effects().contains(&effect) {
+        return Err(format!("Window effect {:?} is not supported on this system", effect));
+    }
+
+    if let Some(target) = app.get_webview_window(&window) {
+        apply_effect(&target, effect)?;
+    }
+
+    settings::update(|s| {
+        if effect == WindowEffect::None {
+            s.window_effects.remove(&window);
+        } else {
+            s.window_effects.insert(window.clone(), effect);
+        }
+    })
+    .map(|_| ())
+}
@@ -0,0 +1,141 @@
+use log::{warn, Level};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Instant;
+
+// Each source may log a burst of BURST entries, then RATE entries per second
+const BURST: f64 = 200.0;
+const RATE: f64 = 50.0;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontendLevel {
+    Trace,
+    Debug,
+    #[default]
+    #[serde(alias = "log")]
+    Info,
+    #[serde(alias = "warning")]
+    Warn,
+    Error,
+}
+
+impl From<FrontendLevel> for Level {
+    fn from(level: FrontendLevel) -> Level {
+        match level {
+            FrontendLevel::Trace => Level::Trace,
+            FrontendLevel::Debug => Level::Debug,
+            FrontendLevel::Info => Level::Info,
+            FrontendLevel::Warn => Level::Warn,
+            FrontendLevel::Error => Level::Error,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    #[serde(default)]
+    pub level: FrontendLevel,
+    pub message: String,
+    // Frontend module, e.g. "notifications" or "core/socket"
+    #[serde(default)]
+    pub module: Option<String>,
+    #[serde(default)]
+    pub fields: BTreeMap<String, Value>,
+}
+
+impl LogEntry {
+    pub fn info(message: String) -> LogEntry {
+        LogEntry {
+            level: FrontendLevel::Info,
+            message,
+            module: None,
+            fields: BTreeMap::new(),
+        }
+    }
+}
+
+// Token bucket per source, with the number of entries dropped since the last one let through
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    dropped: u64,
+}
+
+lazy_static::lazy_static! {
+    static ref BUCKETS: Mutex<HashMap<String, Bucket>> = Mutex::new(HashMap::new());
+}
+
+// Take a token for the source, returning how many entries were dropped before it or None
+// when this entry has to be dropped too
+fn take_token(source: &str) -> Option<u64> {
+    let mut buckets = BUCKETS.lock().ok()?;
+    let now = Instant::now();
+    let bucket = buckets.entry(source.to_string()).or_insert(Bucket {
+        tokens: BURST,
+        updated: now,
+        dropped: 0,
+    });
+
+    let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+    bucket.tokens = (bucket.tokens + elapsed * RATE).min(BURST);
+    bucket.updated = now;
+
+    if bucket.tokens < 1.0 {
+        bucket.dropped += 1;
+        return None;
+    }
+    bucket.tokens -= 1.0;
+    Some(std::mem::take(&mut bucket.dropped))
+}
+
+fn format_fields(fields: &BTreeMap<String, Value>) -> String {
+    fields
+        .iter()
+        .map(|(key, value)| match value {
+            Value::String(s) => format!(" {}={:?}", key, s),
+            other => format!(" {}={}", key, other),
+        })
+        .collect()
+}
+
+// Log entries from a webview. The log target is `webview::<source>[::<module>]`, so the
+// usual module filters apply, e.g. "webview::notifications=debug".
+pub fn log_entries(source: &str, entries: Vec<LogEntry>) {
+    for entry in entries {
+        let Some(dropped) = take_token(source) else {
+            continue;
+        };
+        if dropped > 0 {
+            warn!("Dropped {} log entries from {}", dropped, source);
+        }
+
+        let target = match &entry.module {
+            Some(module) => format!("webview::{}::{}", source, module),
+            None => format!("webview::{}", source),
+        };
+        log::log!(
+            target: target.as_str(),
+            Level::from(entry.level),
+            "{}{}",
+            entry.message,
+            format_fields(&entry.fields)
+        );
+    }
+}
+
+// Payload of a `my-log` event: one entry, a list of entries or a plain message
+pub fn log_event_payload(source: &str, payload: &str) {
+    let entries = if let Ok(entry) = serde_json::from_str::<LogEntry>(payload) {
+        vec![entry]
+    } else if let Ok(entries) = serde_json::from_str::<Vec<LogEntry>>(payload) {
+        entries
+    } else {
+        let message = serde_json::from_str::<String>(payload).unwrap_or_else(|_| payload.to_string());
+        vec![LogEntry::info(message)]
+    };
+    log_entries(source, entries);
+}
@@ -0,0 +1,201 @@
+use log::{info, warn};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use user_idle::UserIdle;
+
+use crate::settings;
+
+pub const DEFAULT_THRESHOLD_SECS: u64 = 300;
+const MIN_THRESHOLD_SECS: u64 = 10;
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// The user is idle past the threshold or the screen is locked
+static AWAY: AtomicBool = AtomicBool::new(false);
+// Kept up to date by the platform's lock notifications, it is not polled
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IdlePayload {
+    idle_seconds: u64,
+    locked: bool,
+}
+
+// Seconds since the last keyboard or mouse input anywhere on the system
+pub fn idle_seconds() -> u64 {
+    match UserIdle::get_time() {
+        Ok(idle) => idle.as_seconds(),
+        Err(e) => {
+            log::debug!("Failed to get idle time: {:?}", e);
+            0
+        }
+    }
+}
+
+fn set_locked(app: &AppHandle, locked: bool) {
+    if LOCKED.swap(locked, Ordering::SeqCst) == locked {
+        return;
+    }
+    info!("Screen {}", if locked { "locked" } else { "unlocked" });
+    update(app, idle_seconds());
+}
+
+// logind's LockedHint, maintained by the screen locker
+#[cfg(target_os = "linux")]
+fn start_lock_listener(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = crate::logind::watch_lock(|locked| set_locked(&app, locked)) {
+            warn!("Failed to monitor the session lock: {}", e);
+        }
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn start_lock_listener(app: &AppHandle) {
+    use objc2_foundation::{NSDistributedNotificationCenter, NSString};
+
+    let center = unsafe { NSDistributedNotificationCenter::defaultCenter() };
+    for (name, locked) in [("com.apple.screenIsLocked", true), ("com.apple.screenIsUnlocked", false)] {
+        let app = app.clone();
+        crate::misc::observe_notification(&center, &NSString::from_str(name), move || {
+            set_locked(&app, locked)
+        });
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows_lock {
+    use log::warn;
+    use std::sync::OnceLock;
+    use tauri::AppHandle;
+    use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows_sys::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows_sys::Win32::System::RemoteDesktop::{WTSRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW, HWND_MESSAGE, MSG,
+        WM_WTSSESSION_CHANGE, WNDCLASSW, WTS_SESSION_LOCK, WTS_SESSION_UNLOCK,
+    };
+
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+
+    unsafe extern "system" fn window_proc(hwnd: HWND, message: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if message == WM_WTSSESSION_CHANGE {
+            if let Some(app) = APP.get() {
+                match wparam as u32 {
+                    WTS_SESSION_LOCK => super::set_locked(app, true),
+                    WTS_SESSION_UNLOCK => super::set_locked(app, false),
+                    _ => {}
+                }
+            }
+        }
+        DefWindowProcW(hwnd, message, wparam, lparam)
+    }
+
+    // Session changes are sent to a window, a hidden message-only one with its own message loop
+    pub fn register(app: &AppHandle) {
+        if APP.set(app.clone()).is_err() {
+            return;
+        }
+        std::thread::spawn(|| unsafe {
+            let class_name: Vec<u16> = "YellowSessionMonitor\0".encode_utf16().collect();
+            let instance = GetModuleHandleW(std::ptr::null());
+            let mut class: WNDCLASSW = std::mem::zeroed();
+            class.lpfnWndProc = Some(window_proc);
+            class.hInstance = instance;
+            class.lpszClassName = class_name.as_ptr();
+            if RegisterClassW(&class) == 0 {
+                warn!("Failed to register the session monitor window class");
+                return;
+            }
+
+            let hwnd = CreateWindowExW(
+                0,
+                class_name.as_ptr(),
+                std::ptr::null(),
+                0,
+                0,
+                0,
+                0,
+                0,
+                HWND_MESSAGE,
+                std::ptr::null_mut(),
+                instance,
+                std::ptr::null(),
+            );
+            if hwnd.is_null() || WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) == 0 {
+                warn!("Failed to register for session lock notifications");
+                return;
+            }
+
+            let mut message: MSG = std::mem::zeroed();
+            while GetMessageW(&mut message, std::ptr::null_mut(), 0, 0) > 0 {
+                DispatchMessageW(&message);
+            }
+        });
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn start_lock_listener(app: &AppHandle) {
+    windows_lock::register(app);
+}
+
+fn threshold() -> u64 {
+    settings::get()
+        .idle_threshold_secs
+        .unwrap_or(DEFAULT_THRESHOLD_SECS)
+}
+
+// Emit user-idle or user-active when the away state changes
+fn update(app: &AppHandle, idle_seconds: u64) {
+    let locked = LOCKED.load(Ordering::SeqCst);
+    let away = locked || idle_seconds >= threshold();
+    if away == AWAY.swap(away, Ordering::SeqCst) {
+        return;
+    }
+
+    let event = if away { "user-idle" } else { "user-active" };
+    info!("{} after {}s idle, screen locked: {}", event, idle_seconds, locked);
+    if let Err(e) = app.emit(event, IdlePayload { idle_seconds, locked }) {
+        warn!("Failed to emit {}: {}", event, e);
+    }
+}
+
+// Poll the idle time and listen for the screen lock, emitting user-idle and user-active on transitions
+pub fn start(app: &AppHandle) {
+    start_lock_listener(app);
+
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POLL_INTERVAL);
+        if crate::power::is_suspended() {
+            continue;
+        }
+        update(&app, idle_seconds());
+    });
+}
+
+#[tauri::command]
+pub fn get_idle_seconds() -> u64 {
+    idle_seconds()
+}
+
+#[tauri::command]
+pub fn get_idle_threshold() -> u64 {
+    threshold()
+}
+
+#[tauri::command]
+pub fn set_idle_threshold(seconds: u64) -> Result<(), String> {
+    if seconds < MIN_THRESHOLD_SECS {
+        return Err(format!(
+            "Idle threshold must be at least {} seconds",
+            MIN_THRESHOLD_SECS
+        ));
+    }
+    info!("Setting idle threshold: {}s", seconds);
+    settings::update(|s| s.idle_threshold_secs = Some(seconds)).map(|_| ())
+}
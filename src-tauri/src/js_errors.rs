@@ -0,0 +1,132 @@
+use log::info;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::WebviewWindow;
+
+// The same error from the same window is reported once per DEDUPE_WINDOW
+const DEDUPE_WINDOW: Duration = Duration::from_secs(60);
+const MAX_TRACKED: usize = 500;
+
+// Sent by the handlers from misc::get_error_handler_script
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsError {
+    // "error" or "unhandledrejection"
+    pub kind: String,
+    pub message: String,
+    pub stack: Option<String>,
+    // file:line:column of the throwing script
+    pub source: Option<String>,
+    pub url: String,
+    pub user_agent: String,
+}
+
+struct Seen {
+    first: Instant,
+    repeats: u64,
+}
+
+lazy_static::lazy_static! {
+    static ref SEEN: Mutex<HashMap<String, Seen>> = Mutex::new(HashMap::new());
+}
+
+// Errors are told apart by window, message and the top stack frame
+fn fingerprint(label: &str, error: &JsError) -> String {
+    let top_frame = error
+        .stack
+        .as_deref()
+        .and_then(|stack| stack.lines().map(str::trim).find(|line| line.starts_with("at ") || line.contains('@')))
+        .unwrap_or_default();
+    format!("{}|{}|{}", label, error.message, top_frame)
+}
+
+// Number of earlier reports of this error within the dedupe window
+fn repeats(key: String) -> u64 {
+    let Ok(mut seen) = SEEN.lock() else {
+        return 0;
+    };
+    let now = Instant::now();
+    seen.retain(|_, s| now.duration_since(s.first) < DEDUPE_WINDOW);
+    if seen.len() >= MAX_TRACKED {
+        seen.clear();
+    }
+
+    let entry = seen.entry(key).or_insert(Seen {
+        first: now,
+        repeats: 0,
+    });
+    let repeats = entry.repeats;
+    entry.repeats += 1;
+    repeats
+}
+
+#[cfg(all(desktop, feature = "sentry"))]
+fn report_to_sentry(label: &str, error: &JsError, repeats: u64) {
+    use tauri_plugin_sentry::sentry;
+    use sentry::protocol::{Breadcrumb, Event, Exception, Level};
+
+    // Repeats only leave a trail for the next event
+    if repeats > 0 {
+        sentry::add_breadcrumb(Breadcrumb {
+            category: Some("js-error".to_string()),
+            message: Some(format!("{}: {}", label, error.message)),
+            level: Level::Error,
+            ..Default::default()
+        });
+        return;
+    }
+
+    let mut event = Event {
+        level: Level::Error,
+        platform: "javascript".into(),
+        logger: Some(format!("webview::{}", label)),
+        exception: vec![Exception {
+            ty: error.kind.clone(),
+            value: Some(error.message.clone()),
+            ..Default::default()
+        }]
+        .into(),
+        ..Default::default()
+    };
+    event.tags.insert("window".to_string(), label.to_string());
+    event.extra.insert("url".to_string(), error.url.clone().into());
+    event.extra.insert("userAgent".to_string(), error.user_agent.clone().into());
+    if let Some(stack) = &error.stack {
+        event.extra.insert("stack".to_string(), stack.clone().into());
+    }
+    if let Some(source) = &error.source {
+        event.extra.insert("source".to_string(), source.clone().into());
+    }
+    sentry::capture_event(event);
+}
+
+#[tauri::command]
+pub fn report_js_error(window: WebviewWindow, error: JsError) {
+    let label = window.label();
+    let repeats = repeats(fingerprint(label, &error));
+    let target = format!("webview::{}", label);
+
+    if repeats == 0 {
+        log::error!(
+            target: target.as_str(),
+            "{} {}: {} at {} ({}, {})\n{}",
+            error.kind,
+            label,
+            error.message,
+            error.source.as_deref().unwrap_or("unknown"),
+            error.url,
+            error.user_agent,
+            error.stack.as_deref().unwrap_or("")
+        );
+    } else if repeats.is_power_of_two() {
+        // Keep floods visible without filling the log
+        info!(target: target.as_str(), "{} repeated {} times: {}", error.kind, repeats, error.message);
+    }
+
+    #[cfg(all(desktop, feature = "sentry"))]
+    if crate::crash_reporting::is_active() {
+        report_to_sentry(label, &error, repeats);
+    }
+}
@@ -1,57 +1,142 @@
+mod appearance;
+#[cfg(desktop)]
+mod attention;
 mod audio;
+mod cli;
 mod commands;
+#[cfg(desktop)]
+mod conversation_window;
+#[cfg(desktop)]
+mod crash_reporting;
+mod deeplink;
+mod diagnostics;
+#[cfg(desktop)]
+mod effects;
+mod frontend_log;
+#[cfg(desktop)]
+mod idle;
+mod js_errors;
+mod logging;
+#[cfg(target_os = "linux")]
+mod logind;
+mod metrics;
 mod misc;
+#[cfg(desktop)]
+mod network;
+mod scrub;
+mod settings;
+#[cfg(desktop)]
+mod startup;
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
 mod notifications;
+#[cfg(desktop)]
+mod power;
+#[cfg(desktop)]
+mod profile;
+#[cfg(desktop)]
+mod shortcuts;
+#[cfg(desktop)]
+mod tray;
+mod windows;
+mod zoom;
 
-use log::{info, LevelFilter};
-use tauri::Listener;
+use log::info;
+use tauri::{Listener, Manager};
 
+// Arguments of a second launch, forwarded to the running instance
 #[cfg(desktop)]
-use tauri::Manager;
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SecondInstancePayload {
+    args: Vec<String>,
+    cwd: String,
+    uri: Option<String>,
+    files: Vec<String>,
+    show: bool,
+}
 
-use tauri::{WebviewUrl, WebviewWindowBuilder};
+#[cfg(desktop)]
+fn handle_second_instance(app: &tauri::AppHandle, args: Vec<String>, cwd: String) {
+    use tauri::Emitter;
 
-use serde::Deserialize;
-#[cfg(not(any(target_os = "android", target_os = "ios")))]
-use tauri_plugin_sentry::{minidump, sentry};
+    info!("Second instance launched with args: {:?}, cwd: {}", args, cwd);
 
-// Define the plugin config
-#[derive(Deserialize)]
-struct Config {}
+    let options = cli::parse(args.iter().skip(1)).unwrap_or_else(|e| {
+        log::warn!("Failed to parse second instance arguments: {}", e);
+        // Still open a link that came with arguments this version doesn't understand
+        cli::LaunchOptions {
+            uri: args.iter().skip(1).find(|a| a.starts_with("yellow://")).cloned(),
+            ..Default::default()
+        }
+    });
 
-// Initialize logging
-fn setup_logging() {
-    #[cfg(target_os = "android")]
-    {
-        use android_logger::Config as AndroidConfig;
-        android_logger::init_once(
-            AndroidConfig::default()
-                .with_max_level(LevelFilter::Info)
-                .with_tag("YellowApp")
-        );
-        
-        // Log several messages at different levels for testing
-        log::trace!("TRACE: Android logging initialized with YellowApp tag");
-        log::debug!("DEBUG: Android logging initialized with YellowApp tag");
-        log::info!("INFO: Android logging initialized with YellowApp tag");
-        log::warn!("WARN: Android logging initialized with YellowApp tag");
-        log::error!("ERROR: Android logging initialized with YellowApp tag");
+    // Relative file paths are resolved against the working directory of the second instance
+    let files = options
+        .files
+        .iter()
+        .map(|f| std::path::Path::new(&cwd).join(f).to_string_lossy().into_owned())
+        .collect();
+
+    // A plain relaunch brings the window up, an autostart duplicate stays in the background
+    let show = options.show || !options.minimized;
+
+    if show {
+        if let Err(e) = windows::show_main_window(app) {
+            log::error!("Failed to show main window: {}", e);
+        }
     }
 
-    #[cfg(not(target_os = "android"))]
-    {
-        env_logger::Builder::new()
-            .filter_level(LevelFilter::Debug)
-            //add milliseconds to the logs
-            .format_timestamp(Some(env_logger::fmt::TimestampPrecision::Millis))
-            .init();
-        info!("Desktop logging initialized");
+    if let Some(uri) = &options.uri {
+        deeplink::handle_url(app, uri);
+    }
+
+    let payload = SecondInstancePayload {
+        args,
+        cwd,
+        uri: options.uri,
+        files,
+        show,
+    };
+    if let Err(e) = app.emit("second-instance", payload) {
+        log::warn!("Failed to emit second-instance event: {}", e);
     }
 }
 
+// Deliver yellow:// links from the command line (Windows, Linux), from the OS (macOS, mobile)
+// and from the intent that started the app on mobile
+fn setup_deep_links(app: &tauri::App, cli_uri: Option<&str>) {
+    use tauri_plugin_deep_link::DeepLinkExt;
 
+    // The handler is registered without --profile, so it must launch the default profile
+    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+    if profile::current() == profile::DEFAULT_PROFILE {
+        if let Err(e) = app.deep_link().register_all() {
+            log::warn!("Failed to register deep link schemes: {}", e);
+        }
+    }
+
+    let handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            deeplink::handle_url(&handle, url.as_str());
+        }
+    });
 
+    if let Some(uri) = cli_uri {
+        deeplink::handle_url(app.handle(), uri);
+    }
+
+    #[cfg(any(target_os = "android", target_os = "ios", target_os = "macos"))]
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => {
+            for url in urls {
+                deeplink::handle_url(app.handle(), url.as_str());
+            }
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to get current deep link: {}", e),
+    }
+}
 
 #[cfg(desktop)]
 fn setup_desktop_notifications(_app: &mut tauri::App) {
@@ -60,6 +145,8 @@ fn setup_desktop_notifications(_app: &mut tauri::App) {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    metrics::start();
+
     // Set up a simplified panic hook on Android to avoid thread issues
     #[cfg(target_os = "android")]
     {
@@ -78,22 +165,47 @@ pub fn run() {
         }));
     }
 
-    #[cfg(not(any(target_os = "android", target_os = "ios")))]
-    let client = sentry::init(("https://3d18b31f479eb4d197cf54e7ef5c4291@o4509327469772800.ingest.de.sentry.io/4509327534981200",
-                               sentry::ClientOptions {
-                                   release: sentry::release_name!(),
-                                   auto_session_tracking: true,
-                                   attach_stacktrace: true,
-                                   trim_backtraces: false,
-                                   ..Default::default()
-                               },
-    ));
+    #[cfg(desktop)]
+    let mut context = tauri::generate_context!();
+    #[cfg(not(desktop))]
+    let context = tauri::generate_context!();
+
+    // Crash reporting only runs with the user's consent and a configured DSN
+    #[cfg(desktop)]
+    let crash_reporting_dsn = crash_reporting::dsn(&context);
+    #[cfg(all(desktop, feature = "sentry"))]
+    let crash_reporting = crash_reporting_dsn.as_deref().map(crash_reporting::init);
+    #[cfg(all(desktop, not(feature = "sentry")))]
+    let _ = crash_reporting_dsn;
+    metrics::mark(metrics::Phase::SentryInit);
     // Caution! Everything before here runs in both app and crash reporter processes
-    #[cfg(not(any(target_os = "android", target_os = "ios")))]
-    let _guard = minidump::init(&client);
     // Everything after here runs in only the app process
 
-    setup_logging();
+    // Parsed after the crash reporter has been started, it is launched with its own arguments
+    #[cfg(desktop)]
+    let mut launch_options = cli::parse_or_exit();
+    #[cfg(not(desktop))]
+    let launch_options = cli::LaunchOptions::default();
+
+    logging::init(launch_options.log_level.as_deref());
+    metrics::mark(metrics::Phase::Logging);
+
+    // Switch the identifier, and with it all data directories, to the selected profile
+    #[cfg(desktop)]
+    if let Err(e) = profile::apply(&mut context, launch_options.profile.as_deref()) {
+        eprintln!("error: {}\n\n{}", e, cli::help_text());
+        std::process::exit(2);
+    }
+
+    // Repeated unclean starts fall back to safe mode so the user is not locked out
+    #[cfg(desktop)]
+    {
+        let health = startup::check(&context.config().identifier, launch_options.safe_mode);
+        if health.unclean_starts > 0 {
+            log::warn!("{} unclean start(s) in a row", health.unclean_starts);
+        }
+        launch_options.safe_mode = health.safe_mode;
+    }
     info!("Starting application");
 
     // Print Android-specific info for debugging
@@ -126,35 +238,54 @@ pub fn run() {
     }
 
     #[cfg(desktop)]
-    let mut builder = tauri::Builder::default()
-        .plugin(tauri_plugin_window_state::Builder::new().build())
-        //.plugin(tauri_plugin_log::Builder::new().build())
-        .plugin(tauri_plugin_sentry::init(&client))
-        .plugin(tauri_plugin_positioner::init());
+    let mut builder = tauri::Builder::default().plugin(tauri_plugin_positioner::init());
+
+    #[cfg(all(desktop, feature = "sentry"))]
+    if let Some((client, _)) = &crash_reporting {
+        builder = builder.plugin(tauri_plugin_sentry::init(client));
+    }
 
     #[cfg(not(desktop))]
     let builder = tauri::Builder::default();
 
+    // Saved window state is ignored when resetting it or in safe mode
+    #[cfg(desktop)]
+    let restore_window_state = !launch_options.reset_window_state && !launch_options.safe_mode;
+
+    #[cfg(desktop)]
+    if restore_window_state {
+        // Visibility is not restored so that --minimized and close-to-tray keep working
+        builder = builder.plugin(
+            tauri_plugin_window_state::Builder::new()
+                .with_state_flags(
+                    tauri_plugin_window_state::StateFlags::all()
+                        & !tauri_plugin_window_state::StateFlags::VISIBLE,
+                )
+                .with_filter(|label| !conversation_window::is_conversation_window(label))
+                .build(),
+        );
+    }
+
     #[cfg(desktop)]
     {
         use tauri_plugin_autostart::MacosLauncher;
 
         builder = builder
             .plugin(tauri_plugin_store::Builder::new().build())
-            .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-                let _ = app
-                    .get_webview_window("main")
-                    .expect("no main window")
-                    .set_focus();
+            .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+                handle_second_instance(app, args, cwd);
             }))
             .plugin(tauri_plugin_autostart::init(
                 MacosLauncher::LaunchAgent,
-                Some(vec!["--flag1", "--flag2"]),
+                Some(vec!["--minimized"]),
             ))
             .plugin(tauri_plugin_updater::Builder::new().build())
+            .plugin(shortcuts::plugin())
             .plugin(tauri_plugin_positioner::init())
     }
 
+    let builder = builder.manage(launch_options.clone());
+
     info!("Tauri application starting");
     info!("thread id: {:?}", std::thread::current().id());
     info!("thread name: {:?}", std::thread::current().name());
@@ -168,6 +299,7 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_yellow::init());
 
     #[cfg(not(any(target_os = "android", target_os = "ios")))]
@@ -177,7 +309,8 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_notification::init())
-        .plugin(tauri_plugin_opener::init());
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_deep_link::init());
 
 
     // Core plugins that should run only on non-Android platforms
@@ -186,14 +319,49 @@ pub fn run() {
     //    .plugin(tauri_plugin_opener::init())
 
     
-    builder.setup(|app| {
+    builder.setup(move |app| {
+            // Plugins are initialized by the time setup runs
+            metrics::mark(metrics::Phase::Plugins);
+
             let app_handle = app.handle().clone();
 
+            // Only the instance that single-instance let through counts as a start
+            #[cfg(desktop)]
+            startup::begin();
+
+            settings::init(app.path().app_config_dir()?);
+
+            // The sentry plugin only exists in builds with the feature
+            #[cfg(all(desktop, feature = "sentry"))]
+            if crash_reporting::is_active() {
+                app.add_capability(include_str!("../capabilities/sentry/desktop.json"))?;
+            }
+
+            logging::configure_from_settings(launch_options.log_level.as_deref());
+            match app.path().app_log_dir() {
+                Ok(dir) => match logging::attach_file(&dir) {
+                    Ok(path) => info!("Logging to {}", path.display()),
+                    Err(e) => log::warn!("{}", e),
+                },
+                Err(e) => log::warn!("Failed to resolve log directory: {}", e),
+            }
+
+            #[cfg(desktop)]
+            if launch_options.reset_window_state {
+                let state_file = app
+                    .path()
+                    .app_config_dir()?
+                    .join(tauri_plugin_window_state::DEFAULT_FILENAME);
+                info!("Resetting window state: {}", state_file.display());
+                let _ = std::fs::remove_file(state_file);
+            }
+
+            setup_deep_links(app, launch_options.uri.as_deref());
+
             // Set up event listener for notification logs
             let log_handle = app_handle.clone();
             log_handle.listen("my-log", move |event| {
-                let payload = event.payload();
-                info!("my-log: {}", payload);
+                frontend_log::log_event_payload("my-log", event.payload());
             });
 
             // Close notifications window when main window closes
@@ -201,55 +369,93 @@ pub fn run() {
             setup_desktop_notifications(app);
 
             // Create main window explicitly with initialization script
-
-            info!("Creating main window with initialization script");
-
-            let main_window_builder =
-                WebviewWindowBuilder::new(app, "main", WebviewUrl::App("/".into()))
-                    .initialization_script(&misc::get_error_handler_script())
-                    .zoom_hotkeys_enabled(true);
-
-            #[cfg(desktop)]
-            let main_window_builder2 = main_window_builder
-                .title("Yellow")
-                .inner_size(1000.0, 800.0)
-                .center();
-
-            #[cfg(not(desktop))]
-            let main_window_builder2 = main_window_builder;
-
-            let main_window = main_window_builder2
-                .build()
+            // Autostart launches the app with --minimized so it starts hidden in the tray
+            let main_window = windows::create_main_window(app, !launch_options.minimized)
                 .expect("Failed to create main window");
+            metrics::mark(metrics::Phase::MainWindow);
 
-            #[cfg(debug_assertions)]
+            #[cfg(any(debug_assertions, feature = "devtools"))]
             {
-                let do_open_devtools = std::env::var("TAURI_OPEN_DEVTOOLS")
-                    .map(|v| v.eq_ignore_ascii_case("true"))
-                    .unwrap_or(false);
+                let do_open_devtools = launch_options.devtools
+                    || std::env::var("TAURI_OPEN_DEVTOOLS")
+                        .map(|v| v.eq_ignore_ascii_case("true"))
+                        .unwrap_or(false);
                 if do_open_devtools {
                     main_window.open_devtools();
                 }
             }
-            #[cfg(not(debug_assertions))]
+            #[cfg(not(any(debug_assertions, feature = "devtools")))]
             {
                 let _ = &main_window;
+                if launch_options.devtools {
+                    log::warn!("--devtools requires a debug build or the `devtools` feature");
+                }
             }
 
-            //  todo pub fn background_throttling(mut self, policy: Option<BackgroundThrottlingPolicy>) -> Self {
+            #[cfg(desktop)]
+            startup::apply_safe_mode(app.handle());
+
+            #[cfg(desktop)]
+            tray::setup_tray(app)?;
+
+            #[cfg(desktop)]
+            shortcuts::register_all(app.handle());
+
+            #[cfg(desktop)]
+            idle::start(app.handle());
+
+            #[cfg(desktop)]
+            {
+                power::start(app.handle());
+                network::start(app.handle());
+            }
+
+            // A start in the tray keeps the pop-outs for the next start with a window
+            #[cfg(desktop)]
+            if !launch_options.safe_mode && !launch_options.minimized {
+                conversation_window::restore_conversation_windows(app.handle());
+            }
 
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
+            cli::get_launch_options,
             commands::get_window_size,
             commands::get_scale_factor,
             commands::log,
+            commands::frontend_ready,
+            logging::get_log_levels,
+            logging::set_log_level,
+            diagnostics::create_diagnostics_bundle,
+            js_errors::report_js_error,
+            #[cfg(desktop)]
+            crash_reporting::get_crash_reporting,
+            #[cfg(desktop)]
+            crash_reporting::set_crash_reporting_consent,
+            #[cfg(desktop)]
+            startup::get_startup_health,
+            #[cfg(desktop)]
+            startup::reset_app_cache,
+            #[cfg(desktop)]
+            startup::restore_autostart,
+            #[cfg(desktop)]
+            profile::get_current_profile,
+            #[cfg(desktop)]
+            profile::list_profiles,
+            #[cfg(desktop)]
+            profile::create_profile,
+            #[cfg(desktop)]
+            profile::delete_profile,
+            #[cfg(desktop)]
+            profile::launch_profile,
             commands::is_debug_mode,
             #[cfg(desktop)]
             commands::get_work_area,
             commands::get_build_commit_hash,
             commands::get_build_branch,
             commands::get_build_ts,
+            commands::get_build_info,
+            metrics::get_runtime_metrics,
             #[cfg(not(any(target_os = "android", target_os = "ios")))]
             notifications::close_notifications_window,
             #[cfg(not(any(target_os = "android", target_os = "ios")))]
@@ -258,10 +464,55 @@ pub fn run() {
             notifications::show_notifications_window,
             #[cfg(not(any(target_os = "android", target_os = "ios")))]
             notifications::hide_notifications_window,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            notifications::get_notifications_mute_status,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            notifications::mute_notifications,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            notifications::set_do_not_disturb,
+            #[cfg(desktop)]
+            tray::set_tray_state,
+            #[cfg(desktop)]
+            shortcuts::list_shortcuts,
+            #[cfg(desktop)]
+            shortcuts::set_shortcut,
+            #[cfg(desktop)]
+            shortcuts::reset_shortcuts,
+            #[cfg(desktop)]
+            conversation_window::open_conversation_window,
+            #[cfg(desktop)]
+            conversation_window::close_conversation_window,
+            #[cfg(desktop)]
+            conversation_window::list_conversation_windows,
+            settings::get_close_policy,
+            settings::set_close_policy,
+            windows::set_background_throttling,
+            windows::get_background_throttling,
+            zoom::get_zoom,
+            zoom::set_zoom,
+            zoom::step_zoom,
+            zoom::reset_zoom,
+            appearance::get_system_appearance,
+            #[cfg(desktop)]
+            idle::get_idle_seconds,
+            #[cfg(desktop)]
+            idle::get_idle_threshold,
+            #[cfg(desktop)]
+            idle::set_idle_threshold,
+            #[cfg(desktop)]
+            network::get_network_status,
+            #[cfg(desktop)]
+            attention::request_attention,
+            #[cfg(desktop)]
+            effects::get_window_effect_support,
+            #[cfg(desktop)]
+            effects::get_window_effect,
+            #[cfg(desktop)]
+            effects::set_window_effect,
             audio::play_audio,
             audio::stop_audio,
             audio::is_audio_playing
         ])
-        .run(tauri::generate_context!())
+        .run(context)
         .expect("error while running tauri application");
 }
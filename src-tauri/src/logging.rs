@@ -0,0 +1,500 @@
+use chrono::Utc;
+use log::{info, LevelFilter, Log, Metadata, Record};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+use crate::settings;
+
+const LOG_FILE: &str = "yellow.log";
+const ROTATED_PREFIX: &str = "yellow-";
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+const MAX_ROTATED_FILES: usize = 5;
+const MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+// Lines logged before the log directory is known, written out once it is
+const EARLY_LINES: usize = 1000;
+// Target name that addresses the default level in set_log_level
+const DEFAULT_TARGET: &str = "default";
+// Longest a temporary level may stay in effect
+const MAX_REVERT_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+// Default level and `module=level` directives, the longest matching module wins
+#[derive(Clone, Debug)]
+pub struct Filter {
+    default: LevelFilter,
+    directives: Vec<(String, LevelFilter)>,
+}
+
+impl Filter {
+    fn get(&self, target: &str) -> Option<LevelFilter> {
+        if target == DEFAULT_TARGET {
+            return Some(self.default);
+        }
+        self.directives
+            .iter()
+            .find(|(module, _)| module == target)
+            .map(|(_, level)| *level)
+    }
+
+    // Set the level of a module, or remove its directive with None
+    fn set(&mut self, target: &str, level: Option<LevelFilter>) {
+        if target == DEFAULT_TARGET {
+            self.default = level.unwrap_or(LevelFilter::Info);
+            return;
+        }
+        self.directives.retain(|(module, _)| module != target);
+        if let Some(level) = level {
+            self.directives.push((target.to_string(), level));
+        }
+    }
+
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.directives
+            .iter()
+            .filter(|(module, _)| target.starts_with(module.as_str()))
+            .max_by_key(|(module, _)| module.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.directives
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max)
+    }
+}
+
+// Debug builds log everything useful by default, release builds stay quieter
+pub fn default_filter() -> &'static str {
+    if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "info"
+    }
+}
+
+// Parse a level or a comma separated list of `level` and `module=level` directives
+pub fn parse_filter(filter: &str) -> Result<Filter, String> {
+    let mut parsed = Filter {
+        default: LevelFilter::Info,
+        directives: Vec::new(),
+    };
+    for directive in filter.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+        let (module, level) = match directive.rsplit_once('=') {
+            Some((module, level)) => (Some(module.trim()), level.trim()),
+            None => (None, directive),
+        };
+        let level = LevelFilter::from_str(level)
+            .map_err(|_| format!("invalid log level '{}' in '{}'", level, filter))?;
+        match module {
+            Some(module) => parsed.directives.push((module.to_string(), level)),
+            None => parsed.default = level,
+        }
+    }
+    Ok(parsed)
+}
+
+struct FileSink {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl FileSink {
+    fn open(dir: &Path) -> std::io::Result<FileSink> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(LOG_FILE))?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(FileSink {
+            dir: dir.to_path_buf(),
+            file,
+            size,
+        })
+    }
+
+    fn write(&mut self, line: &str) {
+        if self.size + line.len() as u64 + 1 > MAX_FILE_SIZE {
+            if let Err(e) = self.rotate() {
+                eprintln!("Failed to rotate log file: {}", e);
+            }
+        }
+        if writeln!(self.file, "{}", line).is_ok() {
+            self.size += line.len() as u64 + 1;
+        }
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let _ = self.file.flush();
+        let rotated = format!("{}{}.log", ROTATED_PREFIX, Utc::now().format("%Y%m%d-%H%M%S%.3f"));
+        fs::rename(self.dir.join(LOG_FILE), self.dir.join(rotated))?;
+        *self = FileSink::open(&self.dir)?;
+        remove_old_logs(&self.dir);
+        Ok(())
+    }
+}
+
+// Rotated log files, newest first
+fn rotated_logs(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.starts_with(ROTATED_PREFIX) && name.ends_with(".log"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // Names embed the rotation time
+    files.sort();
+    files.reverse();
+    files
+}
+
+// The current log file followed by the rotated ones, newest first
+pub fn log_files() -> Vec<PathBuf> {
+    log::logger().flush();
+    let dir = FILE_SINK
+        .lock()
+        .ok()
+        .and_then(|sink| sink.as_ref().map(|s| s.dir.clone()));
+    let Some(dir) = dir else {
+        return Vec::new();
+    };
+    std::iter::once(dir.join(LOG_FILE))
+        .chain(rotated_logs(&dir))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+// Keep the newest rotated files that are not older than MAX_AGE
+fn remove_old_logs(dir: &Path) {
+    let now = SystemTime::now();
+    for (index, path) in rotated_logs(dir).into_iter().enumerate() {
+        let too_old = fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > MAX_AGE);
+        if index >= MAX_ROTATED_FILES || too_old {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref FILTER: RwLock<Filter> = RwLock::new(Filter {
+        default: LevelFilter::Info,
+        directives: Vec::new(),
+    });
+    static ref FILE_SINK: Mutex<Option<FileSink>> = Mutex::new(None);
+    static ref EARLY_BUFFER: Mutex<Option<Vec<String>>> = Mutex::new(Some(Vec::new()));
+    // Pending automatic reverts by target: (generation, revert time in ms since the epoch)
+    static ref REVERTS: Mutex<HashMap<String, (u64, u64)>> = Mutex::new(HashMap::new());
+}
+
+static NEXT_REVERT: AtomicU64 = AtomicU64::new(1);
+
+// Same line format on every platform and in every sink
+fn format_record(record: &Record) -> String {
+    format!(
+        "{} {:<5} [{}] {}",
+        Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+        record.level(),
+        record.target(),
+        record.args()
+    )
+}
+
+fn write_to_file(line: &str) {
+    if let Ok(mut sink) = FILE_SINK.lock() {
+        if let Some(sink) = sink.as_mut() {
+            sink.write(line);
+            return;
+        }
+    }
+    if let Ok(mut early) = EARLY_BUFFER.lock() {
+        if let Some(early) = early.as_mut().filter(|lines| lines.len() < EARLY_LINES) {
+            early.push(line.to_string());
+        }
+    }
+}
+
+struct YellowLogger {
+    #[cfg(target_os = "android")]
+    logcat: android_logger::AndroidLogger,
+}
+
+impl Log for YellowLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        let level = FILTER
+            .read()
+            .map(|f| f.level_for(metadata.target()))
+            .unwrap_or(LevelFilter::Info);
+        metadata.level() <= level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format_record(record);
+
+        // Logcat adds its own timestamp and level
+        #[cfg(target_os = "android")]
+        self.logcat.log(record);
+        #[cfg(not(target_os = "android"))]
+        let _ = writeln!(std::io::stderr(), "{}", line);
+
+        write_to_file(&line);
+    }
+
+    fn flush(&self) {
+        if let Ok(mut sink) = FILE_SINK.lock() {
+            if let Some(sink) = sink.as_mut() {
+                let _ = sink.file.flush();
+            }
+        }
+    }
+}
+
+// Install the logger. `cli_filter` comes from --log-level, the settings filter is applied later
+// by `configure_from_settings` once settings are loaded.
+pub fn init(cli_filter: Option<&str>) {
+    let filter = cli_filter
+        .and_then(|f| parse_filter(f).ok())
+        .unwrap_or_else(|| parse_filter(default_filter()).expect("valid default filter"));
+
+    let logger = YellowLogger {
+        #[cfg(target_os = "android")]
+        logcat: android_logger::AndroidLogger::new(
+            android_logger::Config::default()
+                .with_max_level(LevelFilter::Trace)
+                .with_tag("YellowApp"),
+        ),
+    };
+    if log::set_boxed_logger(Box::new(logger)).is_err() {
+        return;
+    }
+    set_filter(filter);
+}
+
+pub fn set_filter(filter: Filter) {
+    log::set_max_level(filter.max_level());
+    if let Ok(mut current) = FILTER.write() {
+        *current = filter;
+    }
+}
+
+// Use the filter from native settings unless one was given on the command line
+pub fn configure_from_settings(cli_filter: Option<&str>) {
+    if cli_filter.is_some() {
+        return;
+    }
+    let Some(filter) = settings::get().log_filter else {
+        return;
+    };
+    match parse_filter(&filter) {
+        Ok(parsed) => {
+            info!("Using log filter from settings: {}", filter);
+            set_filter(parsed);
+        }
+        Err(e) => log::warn!("Ignoring log filter from settings: {}", e),
+    }
+}
+
+// Start writing to the log directory, including what was logged before it was known
+pub fn attach_file(dir: &Path) -> Result<PathBuf, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create log directory: {}", e))?;
+    remove_old_logs(dir);
+
+    let mut sink = FileSink::open(dir).map_err(|e| format!("Failed to open log file: {}", e))?;
+    if sink.size >= MAX_FILE_SIZE {
+        sink.rotate()
+            .map_err(|e| format!("Failed to rotate log file: {}", e))?;
+    }
+
+    let early = EARLY_BUFFER.lock().ok().and_then(|mut early| early.take());
+    for line in early.unwrap_or_default() {
+        sink.write(&line);
+    }
+
+    let mut file_sink = FILE_SINK
+        .lock()
+        .map_err(|e| format!("Failed to lock log file: {}", e))?;
+    *file_sink = Some(sink);
+    Ok(dir.join(LOG_FILE))
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLevels {
+    pub default: String,
+    // Level by module, e.g. "tauri_app_lib::audio" or "webview::main"
+    pub modules: BTreeMap<String, String>,
+    // Targets that revert automatically, with the revert time in ms since the epoch
+    pub reverts_at: BTreeMap<String, u64>,
+}
+
+fn log_levels() -> LogLevels {
+    let filter = FILTER.read().map(|f| f.clone()).unwrap_or(Filter {
+        default: LevelFilter::Info,
+        directives: Vec::new(),
+    });
+    let reverts_at = REVERTS
+        .lock()
+        .map(|reverts| {
+            reverts
+                .iter()
+                .map(|(target, (_, at))| (target.clone(), *at))
+                .collect()
+        })
+        .unwrap_or_default();
+    LogLevels {
+        default: filter.default.to_string().to_lowercase(),
+        modules: filter
+            .directives
+            .iter()
+            .map(|(module, level)| (module.clone(), level.to_string().to_lowercase()))
+            .collect(),
+        reverts_at,
+    }
+}
+
+fn update_filter(target: &str, level: Option<LevelFilter>) {
+    let updated = FILTER.write().ok().map(|mut filter| {
+        filter.set(target, level);
+        filter.clone()
+    });
+    if let Some(filter) = updated {
+        log::set_max_level(filter.max_level());
+    }
+}
+
+#[tauri::command]
+pub fn get_log_levels() -> LogLevels {
+    log_levels()
+}
+
+fn revert_deadline(now: SystemTime, secs: u64) -> Result<SystemTime, String> {
+    let after = Duration::from_secs(secs);
+    if after > MAX_REVERT_AFTER {
+        return Err(format!(
+            "Revert delay of {}s is longer than the maximum of {}s",
+            secs,
+            MAX_REVERT_AFTER.as_secs()
+        ));
+    }
+    now.checked_add(after)
+        .ok_or_else(|| format!("Invalid revert delay: {}s", secs))
+}
+
+// Change the level of a module ("default" for everything else) until restart. None removes
+// the module's own level. With `revert_after_secs` the previous level comes back by itself,
+// unless the target was changed again in the meantime.
+#[tauri::command]
+pub fn set_log_level(
+    target: String,
+    level: Option<String>,
+    revert_after_secs: Option<u64>,
+) -> Result<LogLevels, String> {
+    let target = target.trim().to_string();
+    if target.is_empty() {
+        return Err("Log target must not be empty".to_string());
+    }
+    let level = level
+        .map(|level| {
+            LevelFilter::from_str(&level).map_err(|_| format!("Invalid log level: {}", level))
+        })
+        .transpose()?;
+    let revert_at = revert_after_secs
+        .map(|secs| revert_deadline(SystemTime::now(), secs))
+        .transpose()?;
+
+    let previous = FILTER
+        .read()
+        .map_err(|e| format!("Failed to lock log filter: {}", e))?
+        .get(&target);
+    info!("Setting log level of {} to {:?}, was {:?}", target, level, previous);
+    update_filter(&target, level);
+
+    let generation = NEXT_REVERT.fetch_add(1, Ordering::SeqCst);
+    if let Ok(mut reverts) = REVERTS.lock() {
+        match revert_at {
+            Some(at) => {
+                let at_ms = at
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0);
+                reverts.insert(target.clone(), (generation, at_ms));
+            }
+            None => {
+                reverts.remove(&target);
+            }
+        }
+    }
+
+    if let Some(secs) = revert_after_secs {
+        let target = target.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_secs(secs));
+            let current = REVERTS.lock().is_ok_and(|mut reverts| {
+                let current = reverts.get(&target).is_some_and(|(g, _)| *g == generation);
+                if current {
+                    reverts.remove(&target);
+                }
+                current
+            });
+            if current {
+                info!("Reverting log level of {} to {:?}", target, previous);
+                update_filter(&target, previous);
+            }
+        });
+    }
+
+    Ok(log_levels())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_filters() {
+        let filter = parse_filter("warn, tauri_app_lib=debug,tauri_app_lib::audio=trace").unwrap();
+        assert_eq!(filter.level_for("tao::window"), LevelFilter::Warn);
+        assert_eq!(filter.level_for("tauri_app_lib"), LevelFilter::Debug);
+        assert_eq!(filter.max_level(), LevelFilter::Trace);
+        assert!(parse_filter("info,audio=loud").is_err());
+        assert_eq!(parse_filter("").unwrap().max_level(), LevelFilter::Info);
+    }
+
+    #[test]
+    fn longest_module_wins() {
+        let filter = parse_filter("info,tauri_app_lib=debug,tauri_app_lib::audio=trace").unwrap();
+        assert_eq!(filter.level_for("tauri_app_lib::audio"), LevelFilter::Trace);
+        assert_eq!(filter.level_for("tauri_app_lib::tray"), LevelFilter::Debug);
+        assert_eq!(filter.level_for("tao::window"), LevelFilter::Info);
+    }
+
+    #[test]
+    fn revert_delay_is_capped() {
+        let now = SystemTime::now();
+        assert_eq!(revert_deadline(now, 60), Ok(now + Duration::from_secs(60)));
+        assert!(revert_deadline(now, MAX_REVERT_AFTER.as_secs()).is_ok());
+        assert!(revert_deadline(now, MAX_REVERT_AFTER.as_secs() + 1).is_err());
+        assert!(revert_deadline(now, u64::MAX).is_err());
+    }
+}
@@ -0,0 +1,37 @@
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::OwnedObjectPath;
+
+const DESTINATION: &str = "org.freedesktop.login1";
+const MANAGER_PATH: &str = "/org/freedesktop/login1";
+const MANAGER_INTERFACE: &str = "org.freedesktop.login1.Manager";
+const SESSION_INTERFACE: &str = "org.freedesktop.login1.Session";
+
+fn manager(connection: &Connection) -> zbus::Result<Proxy<'static>> {
+    Proxy::new(connection, DESTINATION, MANAGER_PATH, MANAGER_INTERFACE)
+}
+
+// Call `on_sleep` with true before the system suspends and false after it woke up.
+// Blocks for as long as the connection to the system bus lasts.
+pub fn watch_sleep(mut on_sleep: impl FnMut(bool)) -> zbus::Result<()> {
+    let connection = Connection::system()?;
+    let manager = manager(&connection)?;
+    for message in manager.receive_signal("PrepareForSleep")? {
+        on_sleep(message.body().deserialize::<bool>()?);
+    }
+    Ok(())
+}
+
+// Call `on_lock` with the session's lock state, once right away and then on every change.
+// Screen lockers report the state through the LockedHint property.
+pub fn watch_lock(mut on_lock: impl FnMut(bool)) -> zbus::Result<()> {
+    let connection = Connection::system()?;
+    // "auto" is the caller's session, or the user's graphical session
+    let path: OwnedObjectPath = manager(&connection)?.call("GetSession", &("auto",))?;
+    let session = Proxy::new(&connection, DESTINATION, path, SESSION_INTERFACE)?;
+
+    on_lock(session.get_property::<bool>("LockedHint")?);
+    for change in session.receive_property_changed::<bool>("LockedHint") {
+        on_lock(change.get()?);
+    }
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use log::info;
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+static START: OnceLock<Instant> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Phase {
+    SentryInit,
+    Logging,
+    Plugins,
+    MainWindow,
+    FrontendReady,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseTiming {
+    pub phase: Phase,
+    // Since run() started
+    pub at_ms: u64,
+    // Since the previous phase finished
+    pub duration_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeMetrics {
+    // Since run() started
+    pub uptime_ms: u64,
+    pub startup: Vec<PhaseTiming>,
+    // Resident set size, None where it can't be read
+    pub memory_bytes: Option<u64>,
+    pub threads: Option<u64>,
+    pub windows: Vec<String>,
+    pub audio_sinks: usize,
+}
+
+lazy_static::lazy_static! {
+    static ref PHASES: Mutex<Vec<PhaseTiming>> = Mutex::new(Vec::new());
+}
+
+fn elapsed_ms() -> u64 {
+    START.get_or_init(Instant::now).elapsed().as_millis() as u64
+}
+
+// Called first thing in run(), phases are timed from here
+pub fn start() {
+    START.get_or_init(Instant::now);
+}
+
+// Record that a startup phase finished, only the first time
+pub fn mark(phase: Phase) {
+    let at_ms = elapsed_ms();
+    let Ok(mut phases) = PHASES.lock() else {
+        return;
+    };
+    if phases.iter().any(|p| p.phase == phase) {
+        return;
+    }
+    let previous = phases.iter().map(|p| p.at_ms).max().unwrap_or(0);
+    phases.push(PhaseTiming {
+        phase,
+        at_ms,
+        duration_ms: at_ms.saturating_sub(previous),
+    });
+    info!("Startup phase {:?} done after {} ms", phase, at_ms);
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn process_stats() -> (Option<u64>, Option<u64>) {
+    let Ok(status) = std::fs::read_to_string("/proc/self/status") else {
+        return (None, None);
+    };
+    let field = |name: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|value| value.split_whitespace().next())
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+    (field("VmRSS:").map(|kb| kb * 1024), field("Threads:"))
+}
+
+#[cfg(target_os = "macos")]
+fn process_stats() -> (Option<u64>, Option<u64>) {
+    let pid = std::process::id().to_string();
+    let memory = crate::misc::query("ps", &["-o", "rss=", "-p", &pid])
+        .and_then(|rss| rss.parse::<u64>().ok())
+        .map(|kb| kb * 1024);
+    // One line per thread after the header
+    let threads = crate::misc::query("ps", &["-M", "-p", &pid])
+        .map(|out| out.lines().count().saturating_sub(1) as u64)
+        .filter(|count| *count > 0);
+    (memory, threads)
+}
+
+#[cfg(target_os = "windows")]
+fn process_stats() -> (Option<u64>, Option<u64>) {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+    };
+    use windows_sys::Win32::System::ProcessStatus::{K32GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, GetCurrentProcessId};
+
+    let memory = unsafe {
+        let mut counters: PROCESS_MEMORY_COUNTERS = std::mem::zeroed();
+        counters.cb = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+        if K32GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, counters.cb) != 0 {
+            Some(counters.WorkingSetSize as u64)
+        } else {
+            None
+        }
+    };
+
+    // The snapshot lists the threads of all processes
+    let threads = unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            None
+        } else {
+            let pid = GetCurrentProcessId();
+            let mut entry: THREADENTRY32 = std::mem::zeroed();
+            entry.dwSize = std::mem::size_of::<THREADENTRY32>() as u32;
+            let mut count = 0;
+            let mut more = Thread32First(snapshot, &mut entry) != 0;
+            while more {
+                if entry.th32OwnerProcessID == pid {
+                    count += 1;
+                }
+                more = Thread32Next(snapshot, &mut entry) != 0;
+            }
+            CloseHandle(snapshot);
+            Some(count)
+        }
+    };
+    (memory, threads)
+}
+
+#[cfg(target_os = "ios")]
+fn process_stats() -> (Option<u64>, Option<u64>) {
+    (None, None)
+}
+
+#[tauri::command]
+pub async fn get_runtime_metrics(app: AppHandle) -> RuntimeMetrics {
+    let (memory_bytes, threads) = process_stats();
+    let mut windows: Vec<String> = app.webview_windows().into_keys().collect();
+    windows.sort();
+    RuntimeMetrics {
+        uptime_ms: elapsed_ms(),
+        startup: PHASES.lock().map(|phases| phases.clone()).unwrap_or_default(),
+        memory_bytes,
+        threads,
+        windows,
+        audio_sinks: crate::audio::active_sinks(),
+    }
+}
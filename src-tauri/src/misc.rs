@@ -1,17 +1,42 @@
+#[cfg(desktop)]
+use std::process::Command;
+
 /// Returns a JavaScript script that installs global error handlers
 pub fn get_error_handler_script() -> String {
     let debug_mode = cfg!(debug_assertions);
     let mut res = String::from(r#"
     console.log('[init] installing global error handlers');
 
+    // Forward errors to the native side, at most 50 per page load
+    let reportedErrors = 0;
+    function reportError(kind, error, message, source) {
+      if (reportedErrors >= 50) return;
+      reportedErrors++;
+      try {
+        window.__TAURI_INTERNALS__?.invoke('report_js_error', {
+          error: {
+            kind,
+            message: String(error?.message ?? message ?? error),
+            stack: error?.stack ?? null,
+            source: source ?? null,
+            url: window.location.href,
+            userAgent: navigator.userAgent,
+          },
+        }).catch(() => {});
+      } catch (_) {}
+    }
+
     // catch sync errors
     window.addEventListener('error', event => {
       console.error('window.onerror:', event.error?.message, event.error?.stack);
+      const source = event.filename ? `${event.filename}:${event.lineno}:${event.colno}` : null;
+      reportError('error', event.error, event.message, source);
     });
 
     // catch promise rejections
     window.addEventListener('unhandledrejection', event => {
       console.error('onunhandledrejection:', event.reason, event.reason?.stack);
+      reportError('unhandledrejection', event.reason, null, null);
     });
 
     "#);
@@ -22,5 +47,43 @@ pub fn get_error_handler_script() -> String {
         "#);
     }
 
-    return res;
+    res
+}
+
+/// Trimmed output of a system tool, None when it is missing or fails
+#[cfg(desktop)]
+pub fn query(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = Command::new(program);
+    command.args(args);
+
+    // Don't flash a console window for every query
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Run `callback` every time `center` posts the notification `name`, for the lifetime of the app
+#[cfg(target_os = "macos")]
+pub fn observe_notification(
+    center: &objc2_foundation::NSNotificationCenter,
+    name: &objc2_foundation::NSString,
+    callback: impl Fn() + 'static,
+) {
+    use block2::RcBlock;
+    use objc2_foundation::NSNotification;
+    use std::ptr::NonNull;
+
+    let block = RcBlock::new(move |_: NonNull<NSNotification>| callback());
+    let observer = unsafe { center.addObserverForName_object_queue_usingBlock(Some(name), None, None, &block) };
+    // Stays registered for the lifetime of the app
+    std::mem::forget(observer);
 }
@@ -0,0 +1,117 @@
+use log::{info, warn};
+use serde::Serialize;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkInterface {
+    pub name: String,
+    pub address: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStatus {
+    pub online: bool,
+    pub interfaces: Vec<NetworkInterface>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum NetworkChange {
+    Online,
+    Offline,
+    InterfaceChanged,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NetworkChangedPayload {
+    change: NetworkChange,
+    #[serde(flatten)]
+    status: NetworkStatus,
+}
+
+lazy_static::lazy_static! {
+    static ref LAST_STATUS: Mutex<Option<NetworkStatus>> = Mutex::new(None);
+}
+
+fn is_routable(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => !ip.is_loopback() && !ip.is_link_local() && !ip.is_unspecified(),
+        IpAddr::V6(ip) => {
+            !ip.is_loopback() && !ip.is_unspecified() && (ip.segments()[0] & 0xffc0) != 0xfe80
+        }
+    }
+}
+
+// Interfaces with a routable address. Online means at least one exists,
+// it does not guarantee that the servers are reachable.
+pub fn current_status() -> NetworkStatus {
+    let mut interfaces: Vec<NetworkInterface> = match if_addrs::get_if_addrs() {
+        Ok(addrs) => addrs
+            .into_iter()
+            .filter(|iface| is_routable(&iface.ip()))
+            .map(|iface| NetworkInterface {
+                address: iface.ip().to_string(),
+                name: iface.name,
+            })
+            .collect(),
+        Err(e) => {
+            warn!("Failed to list network interfaces: {}", e);
+            Vec::new()
+        }
+    };
+    interfaces.sort();
+
+    NetworkStatus {
+        online: !interfaces.is_empty(),
+        interfaces,
+    }
+}
+
+// Compare with the last known state and emit network-changed when it differs
+pub fn check_for_changes(app: &AppHandle) {
+    let status = current_status();
+    let previous = match LAST_STATUS.lock() {
+        Ok(mut last) => last.replace(status.clone()),
+        Err(_) => return,
+    };
+    let Some(previous) = previous else {
+        return;
+    };
+    if previous == status {
+        return;
+    }
+
+    let change = match (previous.online, status.online) {
+        (false, true) => NetworkChange::Online,
+        (true, false) => NetworkChange::Offline,
+        _ => NetworkChange::InterfaceChanged,
+    };
+    info!("Network changed: {:?}, interfaces: {:?}", change, status.interfaces);
+    if let Err(e) = app.emit("network-changed", NetworkChangedPayload { change, status }) {
+        warn!("Failed to emit network-changed: {}", e);
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    check_for_changes(app);
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(POLL_INTERVAL);
+        if !crate::power::is_suspended() {
+            check_for_changes(&app);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_network_status() -> NetworkStatus {
+    current_status()
+}
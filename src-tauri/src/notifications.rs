@@ -1,4 +1,7 @@
 use log::info;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[cfg(not(target_os = "android"))]
 use tauri::{Manager, WebviewUrl, WebviewWindowBuilder};
@@ -16,6 +19,11 @@ pub async fn create_notifications_window(_app: tauri::AppHandle) -> Result<(), S
             return Ok(());
         }
 
+        // The notifications window is one of the things left off in safe mode
+        if crate::startup::is_safe_mode() {
+            return Err("Notifications window is disabled in safe mode".to_string());
+        }
+
         //info!("Creating notifications window");
 
         let _notifications_window = WebviewWindowBuilder::new(
@@ -23,7 +31,10 @@ pub async fn create_notifications_window(_app: tauri::AppHandle) -> Result<(), S
             "notifications",
             WebviewUrl::App("/notifications".into()),
         )
-        .initialization_script(&super::misc::get_error_handler_script());
+        .initialization_script(super::misc::get_error_handler_script());
+        let _notifications_window = crate::profile::apply_webview_data(_notifications_window, &_app);
+        let (_notifications_window, throttling) =
+            crate::windows::apply_background_throttling(_notifications_window, "notifications");
 
         #[cfg(not(target_os = "macos"))]
         // "Note that on `macOS` this requires the `macos-private-api` feature flag, enabled under `tauri.conf.json > app > macOSPrivateApi`".
@@ -39,16 +50,19 @@ pub async fn create_notifications_window(_app: tauri::AppHandle) -> Result<(), S
             .always_on_top(true)
             .shadow(false)
             .focused(false)
-            .initialization_script(&misc::get_error_handler_script());
+            .initialization_script(misc::get_error_handler_script());
 
         #[cfg(dev)]
         let _notifications_window4 = _notifications_window3.resizable(true);
         #[cfg(not(dev))]
         let _notifications_window4 = _notifications_window3.visible(false);
 
-        _notifications_window4
+        let notifications_window = _notifications_window4
             .build()
             .map_err(|e| format!("Failed to create notifications window: {}", e))?;
+        crate::windows::record_background_throttling(&notifications_window, throttling);
+        crate::zoom::apply_saved_zoom(&notifications_window);
+        crate::effects::apply_saved_effect(&notifications_window);
 
         info!("Notifications window created successfully");
     }
@@ -118,4 +132,126 @@ pub fn hide_notifications_window(_app: tauri::AppHandle) -> Result<(), String> {
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+
+// Notification mute / do-not-disturb state, shared by the tray menu and the frontend
+#[derive(Default)]
+struct MuteState {
+    muted_until: Option<SystemTime>,
+    do_not_disturb: bool,
+}
+
+lazy_static::lazy_static! {
+    static ref MUTE_STATE: Mutex<MuteState> = Mutex::new(MuteState::default());
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MuteStatus {
+    pub muted: bool,
+    pub muted_until: Option<u64>,
+    pub do_not_disturb: bool,
+}
+
+// Mute notifications for the given duration, or unmute when None
+pub fn mute_for(duration: Option<Duration>) -> Result<(), String> {
+    let muted_until = duration
+        .map(|d| mute_deadline(SystemTime::now(), d))
+        .transpose()?;
+    if let Ok(mut state) = MUTE_STATE.lock() {
+        state.muted_until = muted_until;
+        info!("Notifications muted until: {:?}", state.muted_until);
+    }
+    Ok(())
+}
+
+fn mute_deadline(now: SystemTime, duration: Duration) -> Result<SystemTime, String> {
+    now.checked_add(duration)
+        .ok_or_else(|| format!("Mute duration too long: {}s", duration.as_secs()))
+}
+
+fn minutes_to_duration(minutes: u64) -> Result<Duration, String> {
+    minutes
+        .checked_mul(60)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("Mute duration too long: {} minutes", minutes))
+}
+
+pub fn set_do_not_disturb_state(enabled: bool) {
+    if let Ok(mut state) = MUTE_STATE.lock() {
+        state.do_not_disturb = enabled;
+        info!("Do not disturb: {}", enabled);
+    }
+}
+
+pub fn is_do_not_disturb() -> bool {
+    MUTE_STATE.lock().map(|s| s.do_not_disturb).unwrap_or(false)
+}
+
+// True when notifications should currently be held back (do not disturb or an active mute)
+pub fn is_muted() -> bool {
+    mute_status().muted
+}
+
+pub fn mute_status() -> MuteStatus {
+    let state = match MUTE_STATE.lock() {
+        Ok(state) => state,
+        Err(_) => {
+            return MuteStatus {
+                muted: false,
+                muted_until: None,
+                do_not_disturb: false,
+            }
+        }
+    };
+    let muted_until = state
+        .muted_until
+        .filter(|until| *until > SystemTime::now())
+        .and_then(|until| until.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64);
+    MuteStatus {
+        muted: state.do_not_disturb || muted_until.is_some(),
+        muted_until,
+        do_not_disturb: state.do_not_disturb,
+    }
+}
+
+#[tauri::command]
+pub fn get_notifications_mute_status() -> MuteStatus {
+    mute_status()
+}
+
+#[tauri::command]
+pub fn mute_notifications(app: tauri::AppHandle, minutes: Option<u64>) -> Result<MuteStatus, String> {
+    mute_for(minutes.map(minutes_to_duration).transpose()?)?;
+    crate::tray::refresh(&app);
+    Ok(mute_status())
+}
+
+#[tauri::command]
+pub fn set_do_not_disturb(app: tauri::AppHandle, enabled: bool) -> MuteStatus {
+    set_do_not_disturb_state(enabled);
+    crate::tray::refresh(&app);
+    mute_status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_minutes() {
+        assert_eq!(minutes_to_duration(0), Ok(Duration::ZERO));
+        assert_eq!(minutes_to_duration(90), Ok(Duration::from_secs(90 * 60)));
+        assert!(minutes_to_duration(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn deadline_overflow_is_an_error() {
+        let now = SystemTime::now();
+        assert_eq!(mute_deadline(now, Duration::from_secs(60)), Ok(now + Duration::from_secs(60)));
+        assert!(mute_deadline(now, Duration::from_secs(u64::MAX / 60 * 60)).is_err());
+        assert!(mute_for(Some(Duration::MAX)).is_err());
+    }
+}
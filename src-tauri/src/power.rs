@@ -0,0 +1,173 @@
+use log::{info, warn};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+// The wall clock keeps running while the system sleeps, sleeping threads don't.
+// A tick that took much longer than requested means the system was suspended.
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+const SUSPEND_GAP: Duration = Duration::from_secs(30);
+
+// Several sources can report the same wake-up
+const RESUME_DEBOUNCE: Duration = Duration::from_secs(30);
+
+static SUSPENDED: AtomicBool = AtomicBool::new(false);
+
+lazy_static::lazy_static! {
+    static ref LAST_RESUME: Mutex<Option<Instant>> = Mutex::new(None);
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResumedPayload {
+    // Approximate time spent suspended, when known
+    suspended_seconds: Option<u64>,
+}
+
+pub fn is_suspended() -> bool {
+    SUSPENDED.load(Ordering::SeqCst)
+}
+
+fn on_suspending(app: &AppHandle) {
+    if SUSPENDED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    info!("System suspending");
+    crate::audio::set_all_paused(true);
+    if let Err(e) = app.emit("system-suspending", ()) {
+        warn!("Failed to emit system-suspending: {}", e);
+    }
+}
+
+fn on_resumed(app: &AppHandle, suspended_seconds: Option<u64>) {
+    let was_suspended = SUSPENDED.swap(false, Ordering::SeqCst);
+    if was_suspended {
+        crate::audio::set_all_paused(false);
+    }
+
+    if let Ok(mut last) = LAST_RESUME.lock() {
+        if last.is_some_and(|t| t.elapsed() < RESUME_DEBOUNCE) {
+            return;
+        }
+        *last = Some(Instant::now());
+    }
+
+    info!("System resumed, suspended for {:?}s", suspended_seconds);
+    if let Err(e) = app.emit("system-resumed", ResumedPayload { suspended_seconds }) {
+        warn!("Failed to emit system-resumed: {}", e);
+    }
+
+    // Mutes may have expired and connections dropped while asleep
+    crate::tray::refresh(app);
+    crate::network::check_for_changes(app);
+}
+
+// Detect wake-ups on every platform from jumps of the wall clock
+fn start_watchdog(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut last = SystemTime::now();
+        loop {
+            std::thread::sleep(WATCHDOG_INTERVAL);
+            let now = SystemTime::now();
+            let elapsed = now.duration_since(last).unwrap_or_default();
+            last = now;
+            if elapsed > WATCHDOG_INTERVAL + SUSPEND_GAP {
+                on_resumed(&app, Some((elapsed - WATCHDOG_INTERVAL).as_secs()));
+            }
+        }
+    });
+}
+
+// logind announces sleep with PrepareForSleep(true) and wake-up with PrepareForSleep(false)
+#[cfg(target_os = "linux")]
+fn start_os_listener(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let result = crate::logind::watch_sleep(|sleeping| {
+            if sleeping {
+                on_suspending(&app);
+            } else {
+                on_resumed(&app, None);
+            }
+        });
+        if let Err(e) = result {
+            warn!("Failed to monitor logind, suspend will not be announced: {}", e);
+        }
+    });
+}
+
+#[cfg(target_os = "windows")]
+mod windows_power {
+    use std::ffi::c_void;
+    use std::sync::OnceLock;
+    use tauri::AppHandle;
+    use windows_sys::Win32::System::Power::{
+        PowerRegisterSuspendResumeNotification, DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS, HPOWERNOTIFY,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        DEVICE_NOTIFY_CALLBACK, PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND,
+    };
+
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+
+    unsafe extern "system" fn callback(_context: *const c_void, kind: u32, _setting: *const c_void) -> u32 {
+        if let Some(app) = APP.get() {
+            match kind {
+                PBT_APMSUSPEND => super::on_suspending(app),
+                PBT_APMRESUMEAUTOMATIC => super::on_resumed(app, None),
+                _ => {}
+            }
+        }
+        0
+    }
+
+    pub fn register(app: &AppHandle) {
+        if APP.set(app.clone()).is_err() {
+            return;
+        }
+        // Registered for the lifetime of the process
+        let params = Box::leak(Box::new(DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+            Callback: Some(callback),
+            Context: std::ptr::null_mut(),
+        }));
+        let mut handle: HPOWERNOTIFY = std::ptr::null_mut();
+        let result = unsafe {
+            PowerRegisterSuspendResumeNotification(
+                DEVICE_NOTIFY_CALLBACK,
+                params as *mut DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS as *mut c_void,
+                &mut handle,
+            )
+        };
+        if result != 0 {
+            log::warn!("Failed to register for suspend notifications: error {}", result);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn start_os_listener(app: &AppHandle) {
+    windows_power::register(app);
+}
+
+#[cfg(target_os = "macos")]
+fn start_os_listener(app: &AppHandle) {
+    use objc2_app_kit::{NSWorkspace, NSWorkspaceDidWakeNotification, NSWorkspaceWillSleepNotification};
+
+    let center = unsafe { NSWorkspace::sharedWorkspace().notificationCenter() };
+    let sleep_app = app.clone();
+    crate::misc::observe_notification(&center, unsafe { NSWorkspaceWillSleepNotification }, move || {
+        on_suspending(&sleep_app)
+    });
+    let wake_app = app.clone();
+    crate::misc::observe_notification(&center, unsafe { NSWorkspaceDidWakeNotification }, move || {
+        on_resumed(&wake_app, None)
+    });
+}
+
+pub fn start(app: &AppHandle) {
+    start_os_listener(app);
+    start_watchdog(app);
+}
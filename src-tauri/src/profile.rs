@@ -0,0 +1,338 @@
+use log::{info, warn};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager, WebviewWindowBuilder, Wry};
+
+use crate::cli::LaunchOptions;
+
+pub const DEFAULT_PROFILE: &str = "default";
+const PROFILES_FILE: &str = "profiles.json";
+
+// Identifier from tauri.conf.json and the profile selected for this process
+static BASE_IDENTIFIER: OnceLock<String> = OnceLock::new();
+static CURRENT_PROFILE: OnceLock<String> = OnceLock::new();
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    pub name: String,
+    pub current: bool,
+    pub default: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrentProfile {
+    pub name: String,
+    pub default: bool,
+    // Several profiles exist and none was selected on the command line
+    pub picker_on_launch: bool,
+}
+
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 32 {
+        return Err("Profile name must be 1 to 32 characters long".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        return Err("Profile name may only contain a-z, 0-9, '-' and '_'".to_string());
+    }
+    Ok(())
+}
+
+// Non-default profiles keep their webview data in a separate WKWebView data store, which needs
+// macOS 14
+#[cfg(target_os = "macos")]
+fn check_supported() -> Result<(), String> {
+    let version = tauri_plugin_os::version().to_string();
+    let major = version.split('.').next().and_then(|m| m.parse::<u32>().ok());
+    if major.is_some_and(|major| major < 14) {
+        return Err(format!("Profiles other than '{}' require macOS 14 or later", DEFAULT_PROFILE));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn check_supported() -> Result<(), String> {
+    Ok(())
+}
+
+pub fn identifier_for(base: &str, name: &str) -> String {
+    if name == DEFAULT_PROFILE {
+        base.to_string()
+    } else {
+        format!("{}.profile-{}", base, name)
+    }
+}
+
+// Select the profile before the app is built. Every path tauri and its plugins derive from the
+// identifier (app data, store, logs, window state, webview data, single-instance lock) is then
+// separate per profile, so a second instance of another profile is not swallowed.
+pub fn apply(context: &mut tauri::Context<Wry>, profile: Option<&str>) -> Result<(), String> {
+    let name = profile.unwrap_or(DEFAULT_PROFILE);
+    let base = context.config().identifier.clone();
+    if name != DEFAULT_PROFILE {
+        validate_name(name)?;
+        check_supported()?;
+        // A mistyped name would otherwise start a new, unlisted profile with empty data
+        let registry = dirs::config_dir().map(|dir| dir.join(&base).join(PROFILES_FILE));
+        if !registry.is_some_and(|path| read_names(&path).iter().any(|n| n == name)) {
+            return Err(format!("Profile '{}' does not exist", name));
+        }
+    }
+
+    let identifier = identifier_for(&base, name);
+    info!("Using profile '{}' with identifier {}", name, identifier);
+
+    context.config_mut().identifier = identifier;
+    let _ = BASE_IDENTIFIER.set(base);
+    let _ = CURRENT_PROFILE.set(name.to_string());
+    Ok(())
+}
+
+pub fn current() -> &'static str {
+    CURRENT_PROFILE.get().map(String::as_str).unwrap_or(DEFAULT_PROFILE)
+}
+
+fn base_identifier(app: &AppHandle) -> String {
+    BASE_IDENTIFIER
+        .get()
+        .cloned()
+        .unwrap_or_else(|| app.config().identifier.clone())
+}
+
+// Separate webview storage for non-default profiles
+pub fn apply_webview_data<'a, M: Manager<Wry>>(
+    builder: WebviewWindowBuilder<'a, Wry, M>,
+    app: &AppHandle,
+) -> WebviewWindowBuilder<'a, Wry, M> {
+    let name = current();
+    if name == DEFAULT_PROFILE {
+        return builder;
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    let builder = {
+        let _ = app;
+        builder.data_store_identifier(data_store_identifier(name))
+    };
+
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    let builder = match app.path().app_local_data_dir() {
+        Ok(dir) => builder.data_directory(dir.join("webview")),
+        Err(e) => {
+            warn!("Failed to resolve webview data directory: {}", e);
+            builder
+        }
+    };
+
+    builder
+}
+
+// WKWebView data stores are identified by 16 bytes instead of a directory
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn data_store_identifier(name: &str) -> [u8; 16] {
+    let fnv = |seed: u64| {
+        name.bytes()
+            .fold(seed, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+    };
+    let mut id = [0u8; 16];
+    id[..8].copy_from_slice(&fnv(0xcbf29ce484222325).to_le_bytes());
+    id[8..].copy_from_slice(&fnv(0x84222325cbf29ce4).to_le_bytes());
+    id
+}
+
+fn registry_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let config_dir = app
+        .path()
+        .config_dir()
+        .map_err(|e| format!("Failed to resolve config directory: {}", e))?;
+    Ok(config_dir.join(base_identifier(app)).join(PROFILES_FILE))
+}
+
+// Names of the non-default profiles
+fn read_names(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str::<Vec<String>>(&text).ok())
+        .unwrap_or_default()
+}
+
+fn read_registry(app: &AppHandle) -> Vec<String> {
+    registry_path(app)
+        .map(|path| read_names(&path))
+        .unwrap_or_default()
+}
+
+fn write_registry(app: &AppHandle, names: &[String]) -> Result<(), String> {
+    let path = registry_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create profiles directory: {}", e))?;
+    }
+    let text = serde_json::to_string_pretty(names)
+        .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+    fs::write(&path, text).map_err(|e| format!("Failed to write profiles: {}", e))
+}
+
+// All directories derived from a profile's identifier
+fn profile_dirs(app: &AppHandle, identifier: &str) -> Vec<PathBuf> {
+    let path = app.path();
+    #[allow(unused_mut)]
+    let mut dirs: Vec<PathBuf> = [
+        path.config_dir(),
+        path.data_dir(),
+        path.local_data_dir(),
+        path.cache_dir(),
+    ]
+    .into_iter()
+    .filter_map(|dir| dir.ok())
+    .map(|dir| dir.join(identifier))
+    .collect();
+
+    #[cfg(target_os = "macos")]
+    if let Ok(home) = path.home_dir() {
+        dirs.push(home.join("Library/Logs").join(identifier));
+    }
+
+    dirs.dedup();
+    dirs
+}
+
+#[tauri::command]
+pub fn get_current_profile(
+    app: AppHandle,
+    options: tauri::State<'_, LaunchOptions>,
+) -> CurrentProfile {
+    let name = current().to_string();
+    CurrentProfile {
+        default: name == DEFAULT_PROFILE,
+        picker_on_launch: options.profile.is_none() && !read_registry(&app).is_empty(),
+        name,
+    }
+}
+
+#[tauri::command]
+pub fn list_profiles(app: AppHandle) -> Vec<ProfileInfo> {
+    let current = current();
+    std::iter::once(DEFAULT_PROFILE.to_string())
+        .chain(read_registry(&app))
+        .map(|name| ProfileInfo {
+            current: name == current,
+            default: name == DEFAULT_PROFILE,
+            name,
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn create_profile(app: AppHandle, name: String) -> Result<Vec<ProfileInfo>, String> {
+    validate_name(&name)?;
+    if name == DEFAULT_PROFILE {
+        return Err("The default profile always exists".to_string());
+    }
+    check_supported()?;
+
+    let mut names = read_registry(&app);
+    if names.contains(&name) {
+        return Err(format!("Profile '{}' already exists", name));
+    }
+
+    info!("Creating profile '{}'", name);
+    names.push(name);
+    names.sort();
+    write_registry(&app, &names)?;
+    Ok(list_profiles(app))
+}
+
+#[tauri::command]
+pub fn delete_profile(app: AppHandle, name: String) -> Result<Vec<ProfileInfo>, String> {
+    if name == DEFAULT_PROFILE {
+        return Err("The default profile cannot be deleted".to_string());
+    }
+    if name == current() {
+        return Err("The profile in use cannot be deleted".to_string());
+    }
+
+    let mut names = read_registry(&app);
+    if !names.contains(&name) {
+        return Err(format!("Profile '{}' does not exist", name));
+    }
+
+    info!("Deleting profile '{}'", name);
+    let identifier = identifier_for(&base_identifier(&app), &name);
+    for dir in profile_dirs(&app, &identifier) {
+        if dir.exists() {
+            fs::remove_dir_all(&dir)
+                .map_err(|e| format!("Failed to delete {}: {}", dir.display(), e))?;
+        }
+    }
+
+    names.retain(|n| n != &name);
+    write_registry(&app, &names)?;
+    Ok(list_profiles(app))
+}
+
+// Restart the app in another profile, used by the profile picker
+#[tauri::command]
+pub fn launch_profile(app: AppHandle, name: String) -> Result<(), String> {
+    if name == current() {
+        return Ok(());
+    }
+    if name != DEFAULT_PROFILE && !read_registry(&app).contains(&name) {
+        return Err(format!("Profile '{}' does not exist", name));
+    }
+
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to get executable path: {}", e))?;
+    let mut command = std::process::Command::new(exe);
+    if name != DEFAULT_PROFILE {
+        check_supported()?;
+        command.args(["--profile", &name]);
+    }
+
+    info!("Launching profile '{}'", name);
+    command
+        .spawn()
+        .map_err(|e| format!("Failed to launch profile: {}", e))?;
+    app.exit(0);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_names() {
+        for name in ["work", "test-2", "a_b"] {
+            assert_eq!(validate_name(name), Ok(()), "{}", name);
+        }
+        for name in ["", "Work", "a b", "../x", &"a".repeat(33)] {
+            assert!(validate_name(name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn only_other_profiles_get_their_own_identifier() {
+        assert_eq!(identifier_for("org.example.yellow", DEFAULT_PROFILE), "org.example.yellow");
+        assert_eq!(identifier_for("org.example.yellow", "work"), "org.example.yellow.profile-work");
+    }
+
+    #[test]
+    fn missing_or_corrupt_registry_is_empty() {
+        let dir = std::env::temp_dir().join(format!("yellow-profiles-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(PROFILES_FILE);
+        assert!(read_names(&path).is_empty());
+        fs::write(&path, "not json").unwrap();
+        assert!(read_names(&path).is_empty());
+        fs::write(&path, r#"["work"]"#).unwrap();
+        assert_eq!(read_names(&path), ["work"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
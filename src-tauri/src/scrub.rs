@@ -0,0 +1,348 @@
+use serde_json::Value;
+
+const REDACTED: &str = "[redacted]";
+
+// Words in a key name that mark its value as secret
+const SECRET_WORDS: [&str; 10] = [
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "credential",
+    "credentials",
+    "authorization",
+    "cookie",
+    "key",
+    "dsn",
+];
+
+// Words in a key name that mark its value as message content, which can contain spaces
+const CONTENT_WORDS: [&str; 4] = ["message", "body", "text", "content"];
+
+// Split snake_case, kebab-case, dotted and camelCase names into lowercase words
+fn key_words(key: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut previous_lower = false;
+    for c in key.chars() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            previous_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && previous_lower && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        word.extend(c.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn has_word(key: &str, words: &[&str]) -> bool {
+    key_words(key).iter().any(|word| words.contains(&word.as_str()))
+}
+
+fn is_content_key(key: &str) -> bool {
+    has_word(key, &CONTENT_WORDS)
+}
+
+pub fn is_sensitive_key(key: &str) -> bool {
+    has_word(key, &SECRET_WORDS) || is_content_key(key)
+}
+
+// Replace the values of sensitive keys anywhere in a JSON document
+pub fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_sensitive_key(key) && !value.is_null() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == '.'
+}
+
+fn is_value_end(c: char) -> bool {
+    c.is_whitespace() || matches!(c, ',' | ';' | '&' | ')' | ']' | '}')
+}
+
+// Redact the values of sensitive `key=value`, `key: value` and `"key":"value"` pairs in a line of text.
+// Content is redacted up to the end of the line, it is not delimited by spaces.
+fn scrub_line(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::with_capacity(line.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        if !is_key_char(chars[i]) || (i > 0 && is_key_char(chars[i - 1])) {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        // A key, optionally quoted and followed by `=` or `:`
+        let start = i;
+        while i < chars.len() && is_key_char(chars[i]) {
+            i += 1;
+        }
+        let key: String = chars[start..i].iter().collect();
+        out.push_str(&key);
+
+        let mut j = i;
+        if j < chars.len() && (chars[j] == '"' || chars[j] == '\'') {
+            j += 1;
+        }
+        while j < chars.len() && chars[j] == ' ' {
+            j += 1;
+        }
+        if j >= chars.len() || !(chars[j] == '=' || chars[j] == ':') || !is_sensitive_key(&key) {
+            continue;
+        }
+        j += 1;
+        while j < chars.len() && chars[j] == ' ' {
+            j += 1;
+        }
+        out.extend(&chars[i..j]);
+
+        if is_content_key(&key) {
+            if j < chars.len() {
+                out.push_str(REDACTED);
+            }
+            i = chars.len();
+            continue;
+        }
+
+        // The value, up to its closing quote or the next separator
+        if j < chars.len() && (chars[j] == '"' || chars[j] == '\'') {
+            let quote = chars[j];
+            let mut k = j + 1;
+            while k < chars.len() && chars[k] != quote {
+                k += if chars[k] == '\\' { 2 } else { 1 };
+            }
+            out.push(quote);
+            out.push_str(REDACTED);
+            if k < chars.len() {
+                out.push(quote);
+            }
+            i = (k + 1).min(chars.len());
+        } else {
+            let mut k = j;
+            while k < chars.len() && !is_value_end(chars[k]) {
+                k += 1;
+            }
+            if k > j {
+                out.push_str(REDACTED);
+            }
+            i = k;
+        }
+    }
+    out
+}
+
+// Log lines start with a timestamp, lines that don't continue the previous record
+fn is_record_start(line: &str) -> bool {
+    let bytes = line.as_bytes();
+    bytes.len() > 10 && bytes[..4].iter().all(u8::is_ascii_digit) && bytes[4] == b'-' && bytes[10] == b'T'
+}
+
+// Target and the byte offset of the message of a record line, `<time> <level> [<target>] <message>`
+fn split_record(line: &str) -> Option<(&str, usize)> {
+    let open = line.find(" [")? + 2;
+    let close = open + line[open..].find("] ")?;
+    Some((&line[open..close], close + 2))
+}
+
+// Scrub a log file in logging::format_record's format. Messages from webviews are dropped as a
+// whole, including continuation lines, since they can carry anything the frontend logged.
+pub fn scrub_log(text: &str) -> String {
+    let mut from_webview = false;
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        if is_record_start(line) {
+            from_webview = false;
+            if let Some((target, message)) = split_record(line) {
+                if target.starts_with("webview::") {
+                    from_webview = true;
+                    lines.push(format!("{}{}", &line[..message], REDACTED));
+                    continue;
+                }
+            }
+        } else if from_webview {
+            continue;
+        }
+        lines.push(scrub_line(line));
+    }
+    lines.join("\n")
+}
+
+#[cfg(all(desktop, feature = "sentry"))]
+fn is_address_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | '@' | '+')
+}
+
+#[cfg(all(desktop, feature = "sentry"))]
+fn is_ipv4(token: &str) -> bool {
+    let parts: Vec<&str> = token.split('.').collect();
+    parts.len() == 4 && parts.iter().all(|p| !p.is_empty() && p.len() <= 3 && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+// Replace the home directory, the user name, server names after `://`, IPv4 addresses and
+// user@server addresses, for reports that leave the machine
+#[cfg(all(desktop, feature = "sentry"))]
+pub fn scrub_pii(text: &str) -> String {
+    let mut text = text.to_string();
+    if let Some(home) = dirs::home_dir().and_then(|h| h.to_str().map(str::to_string)) {
+        if home.len() > 1 {
+            text = text.replace(&home, "~");
+        }
+    }
+    for var in ["USER", "USERNAME"] {
+        if let Ok(user) = std::env::var(var) {
+            if user.len() > 2 {
+                text = text.replace(&user, "[user]");
+            }
+        }
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if !is_address_char(chars[i]) {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && is_address_char(chars[i]) {
+            i += 1;
+        }
+        let token: String = chars[start..i].iter().collect();
+
+        let after_scheme = out.ends_with("://");
+        let address = token
+            .split_once('@')
+            .is_some_and(|(user, server)| !user.is_empty() && server.contains('.'));
+        if after_scheme {
+            out.push_str("[server]");
+        } else if address {
+            out.push_str("[address]");
+        } else if is_ipv4(&token) {
+            out.push_str("[ip]");
+        } else {
+            out.push_str(&token);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn key_words_split_common_styles() {
+        assert_eq!(key_words("accessToken"), ["access", "token"]);
+        assert_eq!(key_words("API_KEY"), ["api", "key"]);
+        assert_eq!(key_words("crash-reporting.dsn"), ["crash", "reporting", "dsn"]);
+        assert_eq!(key_words("userId2"), ["user", "id2"]);
+    }
+
+    #[test]
+    fn sensitive_keys_match_whole_words() {
+        for key in ["password", "accessToken", "api_key", "Authorization", "messageBody", "text"] {
+            assert!(is_sensitive_key(key), "{}", key);
+        }
+        for key in ["keyboard", "monkey", "username", "server", "contextMenu"] {
+            assert!(!is_sensitive_key(key), "{}", key);
+        }
+    }
+
+    #[test]
+    fn redact_json_replaces_nested_values() {
+        let mut value = json!({
+            "server": "example.org",
+            "shortcuts": { "apiKey": "abc", "toggle": "Ctrl+Y" },
+            "accounts": [{ "password": "hunter2", "name": "alice" }],
+            "token": null,
+        });
+        redact_json(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "server": "example.org",
+                "shortcuts": { "apiKey": "[redacted]", "toggle": "Ctrl+Y" },
+                "accounts": [{ "password": "[redacted]", "name": "alice" }],
+                "token": null,
+            })
+        );
+    }
+
+    #[test]
+    fn scrub_line_redacts_secret_values() {
+        assert_eq!(
+            scrub_line("login user=alice password=hunter2 server=example.org"),
+            "login user=alice password=[redacted] server=example.org"
+        );
+        assert_eq!(scrub_line("token: abc123, retry: 2"), "token: [redacted], retry: 2");
+        assert_eq!(
+            scrub_line(r#"{"sessionToken":"a\"b","id":1}"#),
+            r#"{"sessionToken":"[redacted]","id":1}"#
+        );
+    }
+
+    #[test]
+    fn scrub_line_redacts_content_to_the_end_of_the_line() {
+        assert_eq!(scrub_line("sent message=hello world, see you"), "sent message=[redacted]");
+        assert_eq!(scrub_line(r#"{"id":1,"body":"hi","to":"bob"}"#), r#"{"id":1,"body":[redacted]"#);
+        assert_eq!(scrub_line("text:"), "text:");
+    }
+
+    #[test]
+    fn scrub_line_keeps_other_text() {
+        let line = "keyboard=us monkey=1 window main created";
+        assert_eq!(scrub_line(line), line);
+    }
+
+    #[test]
+    fn scrub_log_drops_webview_messages() {
+        let log = "\
+2025-01-01T10:00:00.000Z INFO  [yellow] Starting application
+2025-01-01T10:00:01.000Z ERROR [webview::main] Uncaught secret chat line
+    at render (app.js:1:2)
+2025-01-01T10:00:02.000Z INFO  [yellow::settings] saved token=abc";
+        assert_eq!(
+            scrub_log(log),
+            "\
+2025-01-01T10:00:00.000Z INFO  [yellow] Starting application
+2025-01-01T10:00:01.000Z ERROR [webview::main] [redacted]
+2025-01-01T10:00:02.000Z INFO  [yellow::settings] saved token=[redacted]"
+        );
+    }
+
+    #[cfg(all(desktop, feature = "sentry"))]
+    #[test]
+    fn scrub_pii_replaces_addresses() {
+        assert_eq!(
+            scrub_pii("connect wss://chat.example.org:8084 as alice@example.org from 192.168.1.20"),
+            "connect wss://[server]:8084 as [address] from [ip]"
+        );
+        assert_eq!(scrub_pii("version 1.2.3 of 4 files"), "version 1.2.3 of 4 files");
+    }
+}
@@ -0,0 +1,133 @@
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
+
+const SETTINGS_FILE: &str = "native-settings.json";
+
+// What happens when the user closes the main window
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClosePolicy {
+    Quit,
+    #[default]
+    HideToTray,
+    Minimize,
+}
+
+// Webview background throttling, applied when a window is created
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ThrottlingPolicy {
+    // Keeps websocket heartbeats and timers running while the window is hidden
+    #[default]
+    Disabled,
+    Suspend,
+    Throttle,
+}
+
+// Native window background effect. Effects only show through transparent windows,
+// such as the notifications window.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowEffect {
+    #[default]
+    None,
+    Blur,
+    Acrylic,
+    Mica,
+    Vibrancy,
+}
+
+// Settings owned by the native side, persisted as JSON in the app config directory
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub close_policy: ClosePolicy,
+    // Global shortcut overrides by action, an empty string disables the shortcut
+    pub shortcuts: BTreeMap<String, String>,
+    pub background_throttling: ThrottlingPolicy,
+    // Zoom level by window label
+    pub zoom: BTreeMap<String, f64>,
+    // Window effect by window label
+    pub window_effects: BTreeMap<String, WindowEffect>,
+    // Seconds without input before the user counts as away, None for the default
+    pub idle_threshold_secs: Option<u64>,
+    // Log filter such as "info,tauri_app_lib::audio=debug", overridden by --log-level
+    pub log_filter: Option<String>,
+    // Answer to the first-run crash reporting question, None until asked
+    pub crash_reporting: Option<bool>,
+    // Sentry DSN for self-hosted instances, overrides tauri.conf.json
+    pub crash_reporting_dsn: Option<String>,
+}
+
+lazy_static::lazy_static! {
+    static ref SETTINGS: RwLock<Settings> = RwLock::new(Settings::default());
+    static ref SETTINGS_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+}
+
+// Load settings from the given config directory. Missing or corrupt files fall back to defaults.
+pub fn init(config_dir: PathBuf) {
+    let path = config_dir.join(SETTINGS_FILE);
+    let loaded = match fs::read_to_string(&path) {
+        Ok(text) => serde_json::from_str::<Settings>(&text).unwrap_or_else(|e| {
+            warn!("Failed to parse {}: {}, using defaults", path.display(), e);
+            Settings::default()
+        }),
+        Err(_) => Settings::default(),
+    };
+    info!("Native settings loaded from {}", path.display());
+
+    if let Ok(mut settings) = SETTINGS.write() {
+        *settings = loaded;
+    }
+    if let Ok(mut settings_path) = SETTINGS_PATH.lock() {
+        *settings_path = Some(path);
+    }
+}
+
+pub fn get() -> Settings {
+    SETTINGS.read().map(|s| s.clone()).unwrap_or_default()
+}
+
+// Apply a change to the settings and persist them
+pub fn update<F: FnOnce(&mut Settings)>(f: F) -> Result<Settings, String> {
+    let updated = {
+        let mut settings = SETTINGS
+            .write()
+            .map_err(|e| format!("Failed to lock settings: {}", e))?;
+        f(&mut settings);
+        settings.clone()
+    };
+    save(&updated)?;
+    Ok(updated)
+}
+
+fn save(settings: &Settings) -> Result<(), String> {
+    let path = SETTINGS_PATH
+        .lock()
+        .map_err(|e| format!("Failed to lock settings path: {}", e))?
+        .clone()
+        .ok_or_else(|| "Settings not initialized".to_string())?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+    }
+    let text = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs::write(&path, text).map_err(|e| format!("Failed to write settings: {}", e))
+}
+
+#[tauri::command]
+pub fn get_close_policy() -> ClosePolicy {
+    get().close_policy
+}
+
+#[tauri::command]
+pub fn set_close_policy(policy: ClosePolicy) -> Result<(), String> {
+    info!("Setting close policy: {:?}", policy);
+    update(|s| s.close_policy = policy).map(|_| ())
+}
@@ -0,0 +1,262 @@
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Wry};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::settings;
+
+// System-wide actions that can be bound to a global shortcut
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShortcutAction {
+    ToggleWindow,
+    ToggleMicrophone,
+    AnswerCall,
+    DeclineCall,
+    NextUnread,
+}
+
+const ACTIONS: [ShortcutAction; 5] = [
+    ShortcutAction::ToggleWindow,
+    ShortcutAction::ToggleMicrophone,
+    ShortcutAction::AnswerCall,
+    ShortcutAction::DeclineCall,
+    ShortcutAction::NextUnread,
+];
+
+impl ShortcutAction {
+    fn key(self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default()
+    }
+
+    // Global shortcuts are taken from every other application, so only showing the window is
+    // bound out of the box. The rest are left for the user to bind.
+    fn default_accelerator(self) -> Option<&'static str> {
+        match self {
+            ShortcutAction::ToggleWindow => Some("CommandOrControl+Shift+Y"),
+            ShortcutAction::ToggleMicrophone
+            | ShortcutAction::AnswerCall
+            | ShortcutAction::DeclineCall
+            | ShortcutAction::NextUnread => None,
+        }
+    }
+
+    // Accelerator from settings, falling back to the default. An empty string disables the shortcut.
+    fn accelerator(self) -> Option<String> {
+        match settings::get().shortcuts.get(&self.key()) {
+            Some(accelerator) => Some(accelerator.clone()),
+            None => self.default_accelerator().map(str::to_string),
+        }
+        .filter(|a| !a.is_empty())
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutBinding {
+    pub action: ShortcutAction,
+    pub accelerator: Option<String>,
+    pub default_accelerator: Option<String>,
+    pub registered: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct ShortcutPayload {
+    action: ShortcutAction,
+}
+
+lazy_static::lazy_static! {
+    // Registration result per action: Ok(hotkey id) or the reason it could not be registered
+    static ref REGISTRATIONS: Mutex<HashMap<ShortcutAction, Result<u32, String>>> = Mutex::new(HashMap::new());
+}
+
+pub fn plugin() -> tauri::plugin::TauriPlugin<Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(handle_shortcut)
+        .build()
+}
+
+fn handle_shortcut(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+
+    let action = REGISTRATIONS.lock().ok().and_then(|registrations| {
+        registrations
+            .iter()
+            .find(|(_, r)| matches!(r, Ok(id) if *id == shortcut.id()))
+            .map(|(action, _)| *action)
+    });
+    let Some(action) = action else {
+        return;
+    };
+    info!("Global shortcut: {:?}", action);
+
+    if action == ShortcutAction::ToggleWindow {
+        crate::tray::toggle_main_window(app);
+    }
+    if let Err(e) = app.emit("global-shortcut", ShortcutPayload { action }) {
+        warn!("Failed to emit global-shortcut event: {}", e);
+    }
+}
+
+fn parse(accelerator: &str) -> Result<Shortcut, String> {
+    Shortcut::from_str(accelerator).map_err(|e| format!("Invalid shortcut '{}': {}", accelerator, e))
+}
+
+// Register every configured shortcut, recording conflicts instead of failing
+pub fn register_all(app: &AppHandle) {
+    if let Err(e) = app.global_shortcut().unregister_all() {
+        warn!("Failed to unregister global shortcuts: {}", e);
+    }
+
+    let mut registrations = HashMap::new();
+    let mut taken: Vec<u32> = Vec::new();
+
+    for action in ACTIONS {
+        let Some(accelerator) = action.accelerator() else {
+            continue;
+        };
+        let result = parse(&accelerator).and_then(|shortcut| {
+            if taken.contains(&shortcut.id()) {
+                return Err(format!("'{}' is already bound to another action", accelerator));
+            }
+            app.global_shortcut()
+                .register(shortcut)
+                .map_err(|e| format!("'{}' is already in use: {}", accelerator, e))?;
+            taken.push(shortcut.id());
+            Ok(shortcut.id())
+        });
+        if let Err(e) = &result {
+            warn!("Global shortcut for {:?} not registered: {}", action, e);
+        }
+        registrations.insert(action, result);
+    }
+
+    if let Ok(mut r) = REGISTRATIONS.lock() {
+        *r = registrations;
+    }
+}
+
+fn bindings() -> Vec<ShortcutBinding> {
+    let registrations = REGISTRATIONS.lock().map(|r| r.clone()).unwrap_or_default();
+    ACTIONS
+        .iter()
+        .map(|action| {
+            let registration = registrations.get(action);
+            ShortcutBinding {
+                action: *action,
+                accelerator: action.accelerator(),
+                default_accelerator: action.default_accelerator().map(str::to_string),
+                registered: matches!(registration, Some(Ok(_))),
+                error: registration.and_then(|r| r.clone().err()),
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn list_shortcuts() -> Vec<ShortcutBinding> {
+    bindings()
+}
+
+// Bind an action to an accelerator, or disable it with None. Fails without changing anything
+// when the accelerator is invalid, bound to another action or taken by another application.
+#[tauri::command]
+pub fn set_shortcut(
+    app: AppHandle,
+    action: ShortcutAction,
+    accelerator: Option<String>,
+) -> Result<Vec<ShortcutBinding>, String> {
+    let accelerator = accelerator.map(|a| a.trim().to_string()).filter(|a| !a.is_empty());
+    info!("Setting global shortcut for {:?}: {:?}", action, accelerator);
+
+    let previous = REGISTRATIONS
+        .lock()
+        .map_err(|e| format!("Failed to lock shortcuts: {}", e))?
+        .get(&action)
+        .and_then(|r| r.clone().ok());
+
+    let new_shortcut = match &accelerator {
+        Some(accelerator) => {
+            let shortcut = parse(accelerator)?;
+            let conflict = REGISTRATIONS
+                .lock()
+                .map_err(|e| format!("Failed to lock shortcuts: {}", e))?
+                .iter()
+                .find(|(other, r)| **other != action && matches!(r, Ok(id) if *id == shortcut.id()))
+                .map(|(other, _)| *other);
+            if let Some(other) = conflict {
+                return Err(format!("'{}' is already bound to {:?}", accelerator, other));
+            }
+            if previous != Some(shortcut.id()) && app.global_shortcut().is_registered(shortcut) {
+                return Err(format!("'{}' is already registered", accelerator));
+            }
+            Some((shortcut, previous != Some(shortcut.id())))
+        }
+        None => None,
+    };
+
+    // Register the new shortcut before releasing the old one so a conflict leaves it in place
+    if let Some((shortcut, true)) = new_shortcut {
+        app.global_shortcut()
+            .register(shortcut)
+            .map_err(|e| format!("'{}' is already in use: {}", shortcut, e))?;
+    }
+    if let Some(previous) = previous {
+        if new_shortcut.map(|(s, _)| s.id()) != Some(previous) {
+            let old = action.accelerator().and_then(|a| parse(&a).ok());
+            if let Some(old) = old {
+                let _ = app.global_shortcut().unregister(old);
+            }
+        }
+    }
+
+    settings::update(|s| {
+        s.shortcuts
+            .insert(action.key(), accelerator.clone().unwrap_or_default());
+    })?;
+
+    if let Ok(mut registrations) = REGISTRATIONS.lock() {
+        match new_shortcut {
+            Some((shortcut, _)) => registrations.insert(action, Ok(shortcut.id())),
+            None => registrations.remove(&action),
+        };
+    }
+    Ok(bindings())
+}
+
+#[tauri::command]
+pub fn reset_shortcuts(app: AppHandle) -> Result<Vec<ShortcutBinding>, String> {
+    info!("Resetting global shortcuts");
+    settings::update(|s| s.shortcuts.clear())?;
+    register_all(&app);
+    Ok(bindings())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_keys_are_camel_case() {
+        assert_eq!(ShortcutAction::ToggleWindow.key(), "toggleWindow");
+        assert_eq!(ShortcutAction::NextUnread.key(), "nextUnread");
+    }
+
+    #[test]
+    fn only_toggle_window_is_bound_by_default() {
+        let bound: Vec<ShortcutAction> = ACTIONS
+            .into_iter()
+            .filter(|action| action.default_accelerator().is_some())
+            .collect();
+        assert_eq!(bound, [ShortcutAction::ToggleWindow]);
+    }
+}
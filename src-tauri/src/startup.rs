@@ -0,0 +1,266 @@
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+const MARKER_FILE: &str = "startup-health.json";
+// Unclean starts in a row before the app starts in safe mode
+pub const SAFE_MODE_THRESHOLD: u32 = 3;
+
+// Persisted between starts. `in_progress` is set when a start begins and cleared once the
+// frontend is ready, so a set flag at the next start means that start never completed.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct StartupMarker {
+    in_progress: bool,
+    unclean_starts: u32,
+    last_start: u64,
+    // Safe mode turned autostart off, until the user turns it back on
+    autostart_disabled: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SafeModeReason {
+    CommandLine,
+    CrashLoop,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupHealth {
+    // Earlier starts in a row that never reached the frontend
+    pub unclean_starts: u32,
+    pub threshold: u32,
+    pub safe_mode: bool,
+    pub safe_mode_reason: Option<SafeModeReason>,
+    // Autostart was turned off by an earlier or this safe mode start
+    pub autostart_disabled: bool,
+}
+
+static MARKER_PATH: OnceLock<PathBuf> = OnceLock::new();
+static HEALTH: OnceLock<StartupHealth> = OnceLock::new();
+static COMPLETED: AtomicBool = AtomicBool::new(false);
+static AUTOSTART_DISABLED: AtomicBool = AtomicBool::new(false);
+
+fn read_marker(path: &PathBuf) -> StartupMarker {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn write_marker(path: &PathBuf, marker: &StartupMarker) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    match serde_json::to_string_pretty(marker) {
+        Ok(text) => {
+            if let Err(e) = fs::write(path, text) {
+                warn!("Failed to write startup marker: {}", e);
+            }
+        }
+        Err(e) => warn!("Failed to serialize startup marker: {}", e),
+    }
+}
+
+// Number of unclean starts in a row, counting the previous start if it never completed
+fn unclean_starts(previous: &StartupMarker) -> u32 {
+    if previous.in_progress {
+        previous.unclean_starts.saturating_add(1)
+    } else {
+        0
+    }
+}
+
+fn safe_mode_reason(unclean_starts: u32, requested: bool) -> Option<SafeModeReason> {
+    if requested {
+        Some(SafeModeReason::CommandLine)
+    } else if unclean_starts >= SAFE_MODE_THRESHOLD {
+        Some(SafeModeReason::CrashLoop)
+    } else {
+        None
+    }
+}
+
+// Decide on safe mode before the app is built, without recording anything. This also runs in
+// second instances that single-instance forwards to the running one and then exits.
+// `identifier` is the profile's identifier, `safe_mode` whether --safe-mode was given.
+pub fn check(identifier: &str, safe_mode: bool) -> StartupHealth {
+    let path = dirs::config_dir().map(|dir| dir.join(identifier).join(MARKER_FILE));
+    let previous = path.as_ref().map(read_marker).unwrap_or_default();
+    let unclean_starts = unclean_starts(&previous);
+    let safe_mode_reason = safe_mode_reason(unclean_starts, safe_mode);
+    let health = StartupHealth {
+        unclean_starts,
+        threshold: SAFE_MODE_THRESHOLD,
+        safe_mode: safe_mode_reason.is_some(),
+        safe_mode_reason,
+        autostart_disabled: previous.autostart_disabled,
+    };
+    AUTOSTART_DISABLED.store(previous.autostart_disabled, Ordering::SeqCst);
+
+    if let Some(path) = path {
+        let _ = MARKER_PATH.set(path);
+    }
+    let _ = HEALTH.set(health.clone());
+    health
+}
+
+// Record that this start is in progress. Called from setup, after single-instance has let
+// this process through, so forwarded launches and links are not counted.
+pub fn begin() {
+    let Some(path) = MARKER_PATH.get() else {
+        return;
+    };
+    let marker = StartupMarker {
+        in_progress: true,
+        unclean_starts: HEALTH.get().map_or(0, |health| health.unclean_starts),
+        last_start: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+        autostart_disabled: AUTOSTART_DISABLED.load(Ordering::SeqCst),
+    };
+    write_marker(path, &marker);
+}
+
+// The frontend came up, this start was clean
+pub fn complete() {
+    if COMPLETED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let Some(path) = MARKER_PATH.get() else {
+        return;
+    };
+    let mut marker = read_marker(path);
+    marker.in_progress = false;
+    marker.unclean_starts = 0;
+    write_marker(path, &marker);
+    info!("Startup completed");
+}
+
+fn set_autostart_disabled(disabled: bool) {
+    AUTOSTART_DISABLED.store(disabled, Ordering::SeqCst);
+    let Some(path) = MARKER_PATH.get() else {
+        return;
+    };
+    let mut marker = read_marker(path);
+    marker.autostart_disabled = disabled;
+    write_marker(path, &marker);
+}
+
+pub fn is_safe_mode() -> bool {
+    HEALTH.get().is_some_and(|health| health.safe_mode)
+}
+
+// Safe mode leaves things off that could crash or relaunch the app
+pub fn apply_safe_mode(app: &AppHandle) {
+    if !is_safe_mode() {
+        return;
+    }
+    warn!("Starting in safe mode: {:?}", HEALTH.get().and_then(|h| h.safe_mode_reason));
+
+    use tauri_plugin_autostart::ManagerExt;
+    match app.autolaunch().is_enabled() {
+        Ok(true) => {
+            info!("Disabling autostart in safe mode");
+            match app.autolaunch().disable() {
+                Ok(()) => set_autostart_disabled(true),
+                Err(e) => warn!("Failed to disable autostart: {}", e),
+            }
+        }
+        Ok(false) => {}
+        Err(e) => warn!("Failed to query autostart: {}", e),
+    }
+}
+
+#[tauri::command]
+pub fn get_startup_health() -> StartupHealth {
+    let mut health = HEALTH.get().cloned().unwrap_or(StartupHealth {
+        unclean_starts: 0,
+        threshold: SAFE_MODE_THRESHOLD,
+        safe_mode: false,
+        safe_mode_reason: None,
+        autostart_disabled: false,
+    });
+    health.autostart_disabled = AUTOSTART_DISABLED.load(Ordering::SeqCst);
+    health
+}
+
+// Turn autostart back on after safe mode disabled it, offered by the recovery screen
+#[tauri::command]
+pub fn restore_autostart(app: AppHandle) -> Result<StartupHealth, String> {
+    use tauri_plugin_autostart::ManagerExt;
+    info!("Restoring autostart");
+    app.autolaunch()
+        .enable()
+        .map_err(|e| format!("Failed to enable autostart: {}", e))?;
+    set_autostart_disabled(false);
+    Ok(get_startup_health())
+}
+
+// Remove the app's cache directory, offered by the recovery screen. Takes effect on restart.
+#[tauri::command]
+pub fn reset_app_cache(app: AppHandle) -> Result<(), String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to resolve cache directory: {}", e))?;
+    info!("Resetting cache: {}", dir.display());
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| format!("Failed to reset cache: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marker(in_progress: bool, unclean_starts: u32) -> StartupMarker {
+        StartupMarker {
+            in_progress,
+            unclean_starts,
+            last_start: 0,
+            autostart_disabled: false,
+        }
+    }
+
+    #[test]
+    fn clean_previous_start_resets_the_count() {
+        assert_eq!(unclean_starts(&marker(false, 0)), 0);
+        assert_eq!(unclean_starts(&marker(false, 7)), 0);
+    }
+
+    #[test]
+    fn unfinished_previous_start_is_counted() {
+        assert_eq!(unclean_starts(&marker(true, 0)), 1);
+        assert_eq!(unclean_starts(&marker(true, 2)), 3);
+        assert_eq!(unclean_starts(&marker(true, u32::MAX)), u32::MAX);
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        assert_eq!(unclean_starts(&StartupMarker::default()), 0);
+        let partial: StartupMarker = serde_json::from_str("{\"inProgress\": true}").unwrap();
+        assert_eq!(unclean_starts(&partial), 1);
+    }
+
+    #[test]
+    fn safe_mode_after_threshold() {
+        assert_eq!(safe_mode_reason(0, false), None);
+        assert_eq!(safe_mode_reason(SAFE_MODE_THRESHOLD - 1, false), None);
+        assert_eq!(safe_mode_reason(SAFE_MODE_THRESHOLD, false), Some(SafeModeReason::CrashLoop));
+    }
+
+    #[test]
+    fn command_line_safe_mode_wins() {
+        assert_eq!(safe_mode_reason(0, true), Some(SafeModeReason::CommandLine));
+        assert_eq!(safe_mode_reason(SAFE_MODE_THRESHOLD, true), Some(SafeModeReason::CommandLine));
+    }
+}
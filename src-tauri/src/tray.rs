@@ -0,0 +1,260 @@
+use log::{info, warn};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::notifications;
+
+const TRAY_ID: &str = "main-tray";
+
+const MENU_SHOW_HIDE: &str = "show_hide";
+const MENU_MUTE_1H: &str = "mute_1h";
+const MENU_MUTE_8H: &str = "mute_8h";
+const MENU_UNMUTE: &str = "unmute";
+const MENU_DND: &str = "do_not_disturb";
+const MENU_CHECK_UPDATES: &str = "check_updates";
+const MENU_QUIT: &str = "quit";
+
+// State reflected by the tray icon, pushed from the frontend
+struct TrayState {
+    unread: u32,
+    connected: bool,
+}
+
+lazy_static::lazy_static! {
+    static ref TRAY_STATE: Mutex<TrayState> = Mutex::new(TrayState {
+        unread: 0,
+        connected: true,
+    });
+}
+
+// Menu items that change at runtime
+struct TrayMenuItems {
+    show_hide: MenuItem<tauri::Wry>,
+    do_not_disturb: CheckMenuItem<tauri::Wry>,
+}
+
+#[derive(Clone, Serialize)]
+struct TrayActionPayload {
+    action: String,
+}
+
+pub fn setup_tray(app: &tauri::App) -> tauri::Result<()> {
+    let show_hide = MenuItem::with_id(app, MENU_SHOW_HIDE, "Hide Yellow", true, None::<&str>)?;
+    let mute_1h = MenuItem::with_id(app, MENU_MUTE_1H, "For 1 hour", true, None::<&str>)?;
+    let mute_8h = MenuItem::with_id(app, MENU_MUTE_8H, "For 8 hours", true, None::<&str>)?;
+    let unmute = MenuItem::with_id(app, MENU_UNMUTE, "Unmute", true, None::<&str>)?;
+    let mute = Submenu::with_items(app, "Mute notifications", true, &[&mute_1h, &mute_8h, &unmute])?;
+    let do_not_disturb = CheckMenuItem::with_id(
+        app,
+        MENU_DND,
+        "Do not disturb",
+        true,
+        notifications::is_do_not_disturb(),
+        None::<&str>,
+    )?;
+    let check_updates =
+        MenuItem::with_id(app, MENU_CHECK_UPDATES, "Check for updates", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, MENU_QUIT, "Quit", true, None::<&str>)?;
+
+    let menu = Menu::with_items(
+        app,
+        &[
+            &show_hide,
+            &PredefinedMenuItem::separator(app)?,
+            &mute,
+            &do_not_disturb,
+            &PredefinedMenuItem::separator(app)?,
+            &check_updates,
+            &quit,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Yellow")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(handle_menu_event)
+        .on_tray_icon_event(handle_tray_icon_event);
+
+    if let Some(icon) = render_icon(app.handle()) {
+        builder = builder.icon(icon);
+    }
+
+    builder.build(app)?;
+
+    app.manage(TrayMenuItems {
+        show_hide,
+        do_not_disturb,
+    });
+
+    info!("Tray icon created");
+    Ok(())
+}
+
+fn mute_for(duration: Option<Duration>) {
+    if let Err(e) = notifications::mute_for(duration) {
+        warn!("Failed to mute notifications: {}", e);
+    }
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    let action = event.id().as_ref().to_string();
+    info!("Tray menu action: {}", action);
+
+    match action.as_str() {
+        MENU_SHOW_HIDE => toggle_main_window(app),
+        MENU_MUTE_1H => mute_for(Some(Duration::from_secs(60 * 60))),
+        MENU_MUTE_8H => mute_for(Some(Duration::from_secs(8 * 60 * 60))),
+        MENU_UNMUTE => mute_for(None),
+        MENU_DND => notifications::set_do_not_disturb_state(!notifications::is_do_not_disturb()),
+        _ => {}
+    }
+
+    emit_action(app, &action);
+    refresh(app);
+
+    if action == MENU_QUIT {
+        app.exit(0);
+    }
+}
+
+fn handle_tray_icon_event(tray: &TrayIcon<tauri::Wry>, event: TrayIconEvent) {
+    if let TrayIconEvent::Click {
+        button: MouseButton::Left,
+        button_state: MouseButtonState::Up,
+        ..
+    } = event
+    {
+        let app = tray.app_handle();
+        toggle_main_window(app);
+        emit_action(app, "toggle_window");
+        refresh(app);
+    }
+}
+
+fn emit_action(app: &AppHandle, action: &str) {
+    if let Err(e) = app.emit(
+        "tray-action",
+        TrayActionPayload {
+            action: action.to_string(),
+        },
+    ) {
+        warn!("Failed to emit tray action {}: {}", action, e);
+    }
+}
+
+// Show and focus the main window, or hide it when it is already in front
+pub fn toggle_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let visible = window.is_visible().unwrap_or(false);
+        let minimized = window.is_minimized().unwrap_or(false);
+        if visible && !minimized {
+            let _ = window.hide();
+            return;
+        }
+    }
+
+    if let Err(e) = crate::windows::show_main_window(app) {
+        warn!("Failed to show main window: {}", e);
+    }
+}
+
+// Re-render the tray icon and menu from the current state
+pub fn refresh(app: &AppHandle) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        if let Err(e) = tray.set_icon(render_icon(app)) {
+            warn!("Failed to update tray icon: {}", e);
+        }
+        let _ = tray.set_tooltip(Some(tooltip()));
+    }
+
+    if let Some(items) = app.try_state::<TrayMenuItems>() {
+        let visible = app
+            .get_webview_window("main")
+            .and_then(|w| w.is_visible().ok())
+            .unwrap_or(false);
+        let _ = items
+            .show_hide
+            .set_text(if visible { "Hide Yellow" } else { "Show Yellow" });
+        let _ = items
+            .do_not_disturb
+            .set_checked(notifications::is_do_not_disturb());
+    }
+}
+
+fn tooltip() -> String {
+    let (unread, connected) = TRAY_STATE
+        .lock()
+        .map(|s| (s.unread, s.connected))
+        .unwrap_or((0, true));
+
+    let mut text = String::from("Yellow");
+    if !connected {
+        text.push_str(" - disconnected");
+    } else if unread > 0 {
+        text.push_str(&format!(" - {} unread", unread));
+    }
+    if notifications::is_muted() {
+        text.push_str(" (muted)");
+    }
+    text
+}
+
+// Derive the tray icon from the default window icon: greyed out when disconnected,
+// with a red badge when there are unread messages
+fn render_icon(app: &AppHandle) -> Option<Image<'static>> {
+    let base = app.default_window_icon()?;
+    let (unread, connected) = TRAY_STATE
+        .lock()
+        .map(|s| (s.unread, s.connected))
+        .unwrap_or((0, true));
+
+    let width = base.width();
+    let height = base.height();
+    let mut rgba = base.rgba().to_vec();
+
+    if !connected {
+        for px in rgba.chunks_exact_mut(4) {
+            let grey = ((px[0] as u32 * 30 + px[1] as u32 * 59 + px[2] as u32 * 11) / 100) as u8;
+            px[0] = grey;
+            px[1] = grey;
+            px[2] = grey;
+            px[3] /= 2;
+        }
+    }
+
+    if unread > 0 {
+        let radius = (width.min(height) / 4) as i64;
+        let cx = width as i64 - radius - 1;
+        let cy = radius + 1;
+        for y in 0..height as i64 {
+            for x in 0..width as i64 {
+                let (dx, dy) = (x - cx, y - cy);
+                if dx * dx + dy * dy <= radius * radius {
+                    let i = ((y * width as i64 + x) * 4) as usize;
+                    rgba[i..i + 4].copy_from_slice(&[220, 30, 30, 255]);
+                }
+            }
+        }
+    }
+
+    Some(Image::new_owned(rgba, width, height))
+}
+
+#[tauri::command]
+pub fn set_tray_state(app: AppHandle, unread: u32, connected: bool) -> Result<(), String> {
+    {
+        let mut state = TRAY_STATE
+            .lock()
+            .map_err(|e| format!("Failed to lock tray state: {}", e))?;
+        state.unread = unread;
+        state.connected = connected;
+    }
+    refresh(&app);
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use log::info;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::utils::config::BackgroundThrottlingPolicy;
+use tauri::{Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder, Wry};
+
+use crate::misc;
+use crate::settings::{self, ThrottlingPolicy};
+#[cfg(desktop)]
+use crate::settings::ClosePolicy;
+use tauri::WindowEvent;
+
+// Only WKWebView implements throttling policies (macOS 14+, iOS 17+)
+const THROTTLING_SUPPORTED: bool = cfg!(any(target_os = "macos", target_os = "ios"));
+
+// Holding a Web Lock keeps Chromium and WebKitGTK from freezing a hidden page,
+// the closest thing to disabling throttling where the policy is unsupported
+const KEEP_ALIVE_SCRIPT: &str = r#"
+    if (navigator.locks) {
+      navigator.locks.request('yellow-keep-alive', () => new Promise(() => {}));
+    }
+"#;
+
+lazy_static::lazy_static! {
+    // Policy applied to each window at creation
+    static ref APPLIED_THROTTLING: Mutex<HashMap<String, ThrottlingPolicy>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundThrottlingInfo {
+    // Policy from settings, used for windows created from now on
+    pub configured: ThrottlingPolicy,
+    // Policy each open window was created with
+    pub windows: HashMap<String, ThrottlingPolicy>,
+    // Whether the webview honours the policy natively on this platform
+    pub supported: bool,
+}
+
+// Apply the background throttling policy from settings to a window being created. Pass the
+// returned policy to record_background_throttling once the window was built.
+pub fn apply_background_throttling<'a, M: Manager<Wry>>(
+    builder: WebviewWindowBuilder<'a, Wry, M>,
+    label: &str,
+) -> (WebviewWindowBuilder<'a, Wry, M>, ThrottlingPolicy) {
+    let policy = settings::get().background_throttling;
+    info!("Background throttling for {}: {:?}", label, policy);
+
+    let builder = builder.background_throttling(match policy {
+        ThrottlingPolicy::Disabled => BackgroundThrottlingPolicy::Disabled,
+        ThrottlingPolicy::Suspend => BackgroundThrottlingPolicy::Suspend,
+        ThrottlingPolicy::Throttle => BackgroundThrottlingPolicy::Throttle,
+    });
+
+    let builder = if policy == ThrottlingPolicy::Disabled && !THROTTLING_SUPPORTED {
+        builder.initialization_script(KEEP_ALIVE_SCRIPT)
+    } else {
+        builder
+    };
+    (builder, policy)
+}
+
+pub fn record_background_throttling(window: &WebviewWindow, policy: ThrottlingPolicy) {
+    if let Ok(mut applied) = APPLIED_THROTTLING.lock() {
+        applied.insert(window.label().to_string(), policy);
+    }
+}
+
+#[tauri::command]
+pub fn set_background_throttling(policy: ThrottlingPolicy) -> Result<(), String> {
+    info!("Setting background throttling: {:?}", policy);
+    settings::update(|s| s.background_throttling = policy).map(|_| ())
+}
+
+#[tauri::command]
+pub fn get_background_throttling(app: tauri::AppHandle) -> BackgroundThrottlingInfo {
+    let open = app.webview_windows();
+    let windows = APPLIED_THROTTLING
+        .lock()
+        .map(|applied| {
+            applied
+                .iter()
+                .filter(|(label, _)| open.contains_key(*label))
+                .map(|(label, policy)| (label.clone(), *policy))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    BackgroundThrottlingInfo {
+        configured: settings::get().background_throttling,
+        windows,
+        supported: THROTTLING_SUPPORTED,
+    }
+}
+
+// Create the main window with the error handler initialization script
+pub fn create_main_window<M: Manager<Wry>>(
+    manager: &M,
+    visible: bool,
+) -> tauri::Result<WebviewWindow> {
+    info!("Creating main window with initialization script");
+
+    let main_window_builder =
+        WebviewWindowBuilder::new(manager, "main", WebviewUrl::App("/".into()))
+            .initialization_script(misc::get_error_handler_script())
+            .initialization_script(crate::zoom::get_hotkey_script());
+    let (main_window_builder, throttling) = apply_background_throttling(main_window_builder, "main");
+
+    #[cfg(desktop)]
+    let main_window_builder2 = {
+        let title = match crate::profile::current() {
+            crate::profile::DEFAULT_PROFILE => "Yellow".to_string(),
+            name => format!("Yellow ({})", name),
+        };
+        crate::profile::apply_webview_data(main_window_builder, manager.app_handle())
+            .title(title)
+            .inner_size(1000.0, 800.0)
+            .center()
+            .visible(visible)
+    };
+
+    #[cfg(not(desktop))]
+    let main_window_builder2 = {
+        let _ = visible;
+        main_window_builder
+    };
+
+    let main_window = main_window_builder2.build()?;
+    record_background_throttling(&main_window, throttling);
+    crate::zoom::apply_saved_zoom(&main_window);
+    #[cfg(desktop)]
+    crate::effects::apply_saved_effect(&main_window);
+
+    let window = main_window.clone();
+    main_window.on_window_event(move |event| {
+        match event {
+            // A recreated main window has to signal readiness again before deep links are delivered
+            WindowEvent::Destroyed => crate::deeplink::reset_frontend_ready(),
+            WindowEvent::ThemeChanged(_) => crate::appearance::check_for_changes(window.app_handle()),
+            WindowEvent::Focused(true) => crate::appearance::check_on_focus(window.app_handle()),
+            _ => {}
+        }
+        #[cfg(desktop)]
+        handle_main_window_event(&window, event);
+    });
+
+    Ok(main_window)
+}
+
+// Show, unminimize and focus the main window, recreating it if it was closed
+#[cfg(desktop)]
+pub fn show_main_window(app: &tauri::AppHandle) -> tauri::Result<()> {
+    let window = match app.get_webview_window("main") {
+        Some(window) => window,
+        None => {
+            info!("Main window missing, recreating it");
+            create_main_window(app, true)?
+        }
+    };
+
+    window.show()?;
+    window.unminimize()?;
+    window.set_focus()?;
+    crate::tray::refresh(app);
+    Ok(())
+}
+
+// Apply the configured close policy when the user closes the main window
+#[cfg(desktop)]
+fn handle_main_window_event(window: &WebviewWindow, event: &WindowEvent) {
+    if let WindowEvent::Focused(true) = event {
+        crate::attention::clear(window);
+    }
+
+    if let WindowEvent::CloseRequested { api, .. } = event {
+        let policy = settings::get().close_policy;
+        info!("Main window close requested, policy: {:?}", policy);
+
+        match policy {
+            ClosePolicy::Quit => {
+                window.app_handle().exit(0);
+            }
+            ClosePolicy::HideToTray => {
+                api.prevent_close();
+                let _ = window.hide();
+                crate::tray::refresh(window.app_handle());
+            }
+            ClosePolicy::Minimize => {
+                api.prevent_close();
+                let _ = window.minimize();
+            }
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use log::{info, warn};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::settings;
+
+const MIN_ZOOM: f64 = 0.25;
+const MAX_ZOOM: f64 = 5.0;
+const ZOOM_STEP: f64 = 0.1;
+
+lazy_static::lazy_static! {
+    // Zoom level last applied to each open window, by label
+    static ref LEVELS: Mutex<HashMap<String, f64>> = Mutex::new(HashMap::new());
+}
+
+// Pop-out conversation windows have numbered labels and share one zoom level
+fn zoom_key(label: &str) -> &str {
+    if label.starts_with("conversation-") {
+        "conversation"
+    } else {
+        label
+    }
+}
+
+// Windows without a zoom level of their own follow the main window
+fn fallback_key(label: &str) -> Option<&'static str> {
+    match label {
+        "notifications" => Some("main"),
+        _ => None,
+    }
+}
+
+fn lookup(zoom: &BTreeMap<String, f64>, label: &str) -> f64 {
+    zoom.get(zoom_key(label))
+        .or_else(|| fallback_key(label).and_then(|key| zoom.get(key)))
+        .copied()
+        .unwrap_or(1.0)
+}
+
+fn saved_zoom(label: &str) -> f64 {
+    lookup(&settings::get().zoom, label)
+}
+
+// Zoom level the window is displayed at
+fn current_zoom(label: &str) -> f64 {
+    LEVELS
+        .lock()
+        .ok()
+        .and_then(|levels| levels.get(label).copied())
+        .unwrap_or_else(|| saved_zoom(label))
+}
+
+fn apply(window: &WebviewWindow, level: f64) -> tauri::Result<()> {
+    window.set_zoom(level)?;
+    if let Ok(mut levels) = LEVELS.lock() {
+        levels.insert(window.label().to_string(), level);
+    }
+    Ok(())
+}
+
+// Reapply the persisted zoom level to a newly created window
+pub fn apply_saved_zoom(window: &WebviewWindow) {
+    let level = saved_zoom(window.label());
+    if level != 1.0 {
+        info!("Restoring zoom {} for {}", level, window.label());
+    }
+    if let Err(e) = apply(window, level) {
+        warn!("Failed to restore zoom for {}: {}", window.label(), e);
+    }
+}
+
+// Replaces the webview's own zoom hotkeys, which change the zoom without telling us,
+// so that zooming with the keyboard or Ctrl+wheel is saved like set_zoom
+pub fn get_hotkey_script() -> String {
+    r#"
+    (function () {
+      const isMac = navigator.platform.toUpperCase().includes('MAC');
+      function zoom(step) {
+        const invoke = window.__TAURI_INTERNALS__?.invoke;
+        if (!invoke) return;
+        const result = step === 0 ? invoke('reset_zoom', {}) : invoke('step_zoom', { step });
+        result.catch((e) => console.warn('[zoom]', e));
+      }
+      window.addEventListener('keydown', (event) => {
+        if (!(isMac ? event.metaKey : event.ctrlKey) || event.altKey) return;
+        if (event.key === '=' || event.key === '+') zoom(1);
+        else if (event.key === '-') zoom(-1);
+        else if (event.key === '0') zoom(0);
+        else return;
+        event.preventDefault();
+      });
+      window.addEventListener('wheel', (event) => {
+        if (!event.ctrlKey || event.deltaY === 0) return;
+        event.preventDefault();
+        zoom(event.deltaY < 0 ? 1 : -1);
+      }, { passive: false });
+    })();
+    "#
+    .to_string()
+}
+
+// The window given by label, or the calling window
+fn target_window(app: &AppHandle, window: WebviewWindow, label: Option<String>) -> Result<WebviewWindow, String> {
+    match label {
+        Some(label) => app
+            .get_webview_window(&label)
+            .ok_or_else(|| format!("Window not found: {}", label)),
+        None => Ok(window),
+    }
+}
+
+#[tauri::command]
+pub fn get_zoom(window: WebviewWindow, label: Option<String>) -> f64 {
+    current_zoom(label.as_deref().unwrap_or(window.label()))
+}
+
+#[tauri::command]
+pub fn set_zoom(
+    app: AppHandle,
+    window: WebviewWindow,
+    label: Option<String>,
+    level: f64,
+) -> Result<f64, String> {
+    if !level.is_finite() {
+        return Err(format!("Invalid zoom level: {}", level));
+    }
+    let level = level.clamp(MIN_ZOOM, MAX_ZOOM);
+    let window = target_window(&app, window, label)?;
+    info!("Setting zoom {} for {}", level, window.label());
+
+    apply(&window, level).map_err(|e| format!("Failed to set zoom: {}", e))?;
+    let key = zoom_key(window.label()).to_string();
+    settings::update(|s| {
+        s.zoom.insert(key, level);
+    })?;
+    Ok(level)
+}
+
+// Zoom in or out by `step` increments from the current level, used by the zoom hotkeys
+#[tauri::command]
+pub fn step_zoom(app: AppHandle, window: WebviewWindow, label: Option<String>, step: i32) -> Result<f64, String> {
+    let window = target_window(&app, window, label)?;
+    let level = stepped(current_zoom(window.label()), step);
+    set_zoom(app, window, None, level)
+}
+
+fn stepped(level: f64, step: i32) -> f64 {
+    // Round so repeated steps land on whole percentages
+    ((level + step as f64 * ZOOM_STEP) * 100.0).round() / 100.0
+}
+
+#[tauri::command]
+pub fn reset_zoom(app: AppHandle, window: WebviewWindow, label: Option<String>) -> Result<f64, String> {
+    let window = target_window(&app, window, label)?;
+    info!("Resetting zoom for {}", window.label());
+
+    let key = zoom_key(window.label()).to_string();
+    settings::update(|s| {
+        s.zoom.remove(&key);
+    })?;
+    // Back to the fallback window's level, if any
+    let level = saved_zoom(window.label());
+    apply(&window, level).map_err(|e| format!("Failed to reset zoom: {}", e))?;
+    Ok(level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversation_windows_share_a_key() {
+        assert_eq!(zoom_key("conversation-3"), "conversation");
+        assert_eq!(zoom_key("main"), "main");
+    }
+
+    #[test]
+    fn notifications_follow_the_main_window() {
+        let mut zoom = BTreeMap::new();
+        assert_eq!(lookup(&zoom, "notifications"), 1.0);
+        zoom.insert("main".to_string(), 1.5);
+        assert_eq!(lookup(&zoom, "notifications"), 1.5);
+        assert_eq!(lookup(&zoom, "conversation-1"), 1.0);
+        zoom.insert("notifications".to_string(), 0.8);
+        assert_eq!(lookup(&zoom, "notifications"), 0.8);
+    }
+
+    #[test]
+    fn steps_land_on_whole_percentages() {
+        assert_eq!(stepped(1.0, 1), 1.1);
+        assert_eq!(stepped(1.1, 1), 1.2);
+        assert_eq!(stepped(1.0, -3), 0.7);
+    }
+}